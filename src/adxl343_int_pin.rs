@@ -0,0 +1,208 @@
+use embedded_hal::digital::InputPin;
+#[cfg(feature = "async")]
+use embedded_hal_async::digital::Wait;
+use core::fmt::{Debug, Display};
+use core::error::Error;
+
+use crate::{
+    adxl343_interface::{ADXL343Interface, ADXL343Error},
+    bus::RegisterBus,
+    registers::accel_configs::InterruptPin,
+    utils::interrupts::InterruptSet,
+};
+
+/// Device Interface paired with the GPIO wired to one of the adxl343 interrupt pins. 
+/// The interrupt line is expected to be active high (the default, int_invert = 0)
+pub struct ADXL343PinInterface<B, P>
+where
+    B: RegisterBus,
+{
+    sensor: ADXL343Interface<B>,
+    int_pin: P,
+    pin: InterruptPin,
+}
+
+impl<B, P> ADXL343PinInterface<B, P>
+where
+    B: RegisterBus,
+{
+    /// Pairs the sensor with int_pin, the GPIO connected to the adxl343's INT1 or INT2 (pin)
+    pub fn new(sensor: ADXL343Interface<B>, int_pin: P, pin: InterruptPin) -> Self {
+        Self {
+            sensor,
+            int_pin,
+            pin
+        }
+    }
+
+    /// Access to the wrapped device interface, e.g. to configure the FIFO
+    pub fn sensor(&mut self) -> &mut ADXL343Interface<B> {
+        &mut self.sensor
+    }
+
+    /// returns both the device interface and the GPIO
+    pub fn release(self) -> (ADXL343Interface<B>, P) {
+        (self.sensor, self.int_pin)
+    }
+
+    /// maps interrupts to the wired pin and enables them, skipping the writes when the settings 
+    /// show this has already been done
+    fn route(&mut self, interrupts: InterruptSet) -> Result<(), ADXL343Error<B::Error>> {
+        let settings = *self.sensor.settings();
+        let on_int2 = settings.get_int2_interrupts().contains(interrupts);
+        let on_int1 = !settings.get_int2_interrupts().intersects(interrupts);
+        let mapped = match self.pin {
+            InterruptPin::INT1 => on_int1,
+            InterruptPin::INT2 => on_int2
+        };
+        if !mapped {
+            self.sensor.map_interrupts(interrupts, self.pin)?;
+        }
+        if !settings.get_enabled_interrupts().contains(interrupts) {
+            self.sensor.enable_interrupts(interrupts)?;
+        }
+        Ok(())
+    }
+}
+
+impl<B, P> ADXL343PinInterface<B, P>
+where
+    B: RegisterBus,
+    P: InputPin,
+{
+    /// Blocks until the DATA_READY interrupt asserts the pin, then returns the accel reading 
+    /// [x_axis, y_axis, z_axis]. DATA_READY is routed to the pin and enabled on first use.
+    /// The pin is polled without a limit: the call does not return while the pin stays low (e.g. the 
+    /// device is in standby or the pin is not wired), use the async variant or a watchdog where 
+    /// that matters
+    pub fn wait_for_data_ready(&mut self) -> Result<[f32; 3], ADXL343PinError<B::Error, P::Error>> {
+        self.route(InterruptSet::DATA_READY)?;
+        while !self.int_pin.is_high().map_err(ADXL343PinError::Pin)? {}
+        Ok(self.sensor.read_accel()?)
+    }
+
+    /// Blocks until the WATERMARK interrupt asserts the pin, then drains the FIFO into buffer 
+    /// (see read_fifo_accel). Returns the number of samples written to the buffer.
+    /// The FIFO must already be configured in FIFO or STREAM mode, WATERMARK is routed to the pin 
+    /// and enabled on first use. As in wait_for_data_ready, the pin is polled without a limit
+    pub fn wait_for_watermark(&mut self, buffer: &mut [[f32; 3]]) 
+    -> Result<usize, ADXL343PinError<B::Error, P::Error>> {
+        self.route(InterruptSet::WATERMARK)?;
+        while !self.int_pin.is_high().map_err(ADXL343PinError::Pin)? {}
+        Ok(self.sensor.read_fifo_accel(buffer)?)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<B, P> ADXL343PinInterface<B, P>
+where
+    B: RegisterBus,
+    P: Wait,
+{
+    /// Same as wait_for_data_ready, but awaits the pin instead of polling it
+    pub async fn wait_for_data_ready_async(&mut self) -> Result<[f32; 3], ADXL343PinError<B::Error, P::Error>> {
+        self.route(InterruptSet::DATA_READY)?;
+        self.int_pin.wait_for_high().await.map_err(ADXL343PinError::Pin)?;
+        Ok(self.sensor.read_accel()?)
+    }
+
+    /// Same as wait_for_watermark, but awaits the pin instead of polling it
+    pub async fn wait_for_watermark_async(&mut self, buffer: &mut [[f32; 3]]) 
+    -> Result<usize, ADXL343PinError<B::Error, P::Error>> {
+        self.route(InterruptSet::WATERMARK)?;
+        self.int_pin.wait_for_high().await.map_err(ADXL343PinError::Pin)?;
+        Ok(self.sensor.read_fifo_accel(buffer)?)
+    }
+}
+
+#[derive(Debug)]
+pub enum ADXL343PinError<E, PE>
+{
+    Sensor(ADXL343Error<E>),   // error from the device interface
+    Pin(PE)                    // error from the GPIO
+}
+
+impl<E: Debug, PE: Debug> Error for ADXL343PinError<E, PE>{}
+
+impl<E: Debug, PE: Debug> Display for ADXL343PinError<E, PE> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ADXL343PinError::Sensor(sensor_error) => Display::fmt(sensor_error, f),
+            ADXL343PinError::Pin(pin_error) => pin_error.fmt(f)
+        }
+    }
+}
+
+impl<E, PE> From<ADXL343Error<E>> for ADXL343PinError<E, PE> {
+    fn from(value: ADXL343Error<E>) -> Self {
+        ADXL343PinError::Sensor(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use std::vec;
+    use embedded_hal_mock::eh1::{
+        digital::{Mock as PinMock, State, Transaction as PinTransaction},
+        i2c::{Mock as I2cMock, Transaction as I2cTransaction},
+    };
+    use crate::registers::{ADXL343_ADDR, DATAX0_ADDR, FIFO_STATUS_ADDR, INT_ENABLE_ADDR, INT_MAP_ADDR};
+    use super::*;
+
+    #[test]
+    fn data_ready_on_int2(){
+        let expectations = [
+            I2cTransaction::write(ADXL343_ADDR, vec![INT_MAP_ADDR, 0x80]),
+            I2cTransaction::write(ADXL343_ADDR, vec![INT_ENABLE_ADDR, 0x80]),
+            I2cTransaction::write_read(ADXL343_ADDR, vec![DATAX0_ADDR], vec![0, 0, 0, 0, 0x00, 0x01]),
+            //already routed, no more INT_MAP or INT_ENABLE writes
+            I2cTransaction::write_read(ADXL343_ADDR, vec![DATAX0_ADDR], vec![0x00, 0x01, 0, 0, 0, 0]),
+        ];
+        let pin_expectations = [
+            PinTransaction::get(State::Low),
+            PinTransaction::get(State::Low),
+            PinTransaction::get(State::High),
+            PinTransaction::get(State::High),
+        ];
+        let sensor = ADXL343Interface::new(I2cMock::new(&expectations));
+        let mut interface = ADXL343PinInterface::new(sensor, PinMock::new(&pin_expectations), InterruptPin::INT2);
+        assert_eq!(interface.wait_for_data_ready().unwrap(), [0.0, 0.0, 1.0]);
+        assert_eq!(interface.wait_for_data_ready().unwrap(), [1.0, 0.0, 0.0]);
+        assert_eq!(interface.sensor().settings().get_int2_interrupts(), InterruptSet::DATA_READY);
+
+        let (sensor, mut pin) = interface.release();
+        let (mut i2c, _) = sensor.destroy();
+        i2c.done();
+        pin.done();
+    }
+
+    #[test]
+    fn watermark_on_int1(){
+        let expectations = [
+            //INT1 is the default mapping, only INT_ENABLE is written
+            I2cTransaction::write(ADXL343_ADDR, vec![INT_ENABLE_ADDR, 0x02]),
+            I2cTransaction::write_read(ADXL343_ADDR, vec![FIFO_STATUS_ADDR], vec![0x02]),
+            I2cTransaction::transaction_start(ADXL343_ADDR),
+            I2cTransaction::write(ADXL343_ADDR, vec![DATAX0_ADDR]),
+            I2cTransaction::read(ADXL343_ADDR, vec![0, 0, 0, 0, 0x00, 0x01]),
+            I2cTransaction::write(ADXL343_ADDR, vec![DATAX0_ADDR]),
+            I2cTransaction::read(ADXL343_ADDR, vec![0, 0, 0x00, 0xFF, 0, 0]),
+            I2cTransaction::transaction_end(ADXL343_ADDR),
+        ];
+        let pin_expectations = [
+            PinTransaction::get(State::Low),
+            PinTransaction::get(State::High),
+        ];
+        let sensor = ADXL343Interface::new(I2cMock::new(&expectations));
+        let mut interface = ADXL343PinInterface::new(sensor, PinMock::new(&pin_expectations), InterruptPin::INT1);
+        let mut buffer = [[0f32; 3]; 4];
+        assert_eq!(interface.wait_for_watermark(&mut buffer).unwrap(), 2);
+        assert_eq!(buffer[..2], [[0.0, 0.0, 1.0], [0.0, -1.0, 0.0]]);
+
+        let (sensor, mut pin) = interface.release();
+        let (mut i2c, _) = sensor.destroy();
+        i2c.done();
+        pin.done();
+    }
+}
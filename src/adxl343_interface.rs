@@ -1,60 +1,212 @@
 #![allow(unused)]
-use embedded_hal::i2c::{I2c, Error as I2c_Error};
+use embedded_hal::{i2c::I2c, spi::SpiDevice};
 use crate::registers::REGISTER_SIZE;
 use crate::{
+    bus::{RegisterBus, I2cBus, I2cAddress, SpiBus},
     registers::{
         self, DEVID_ADDR, BW_RATE_ADDR, DATA_FORMAT_ADDR, DATAX0_ADDR, ADXL343_ADDR, DEVID_REG_VALUE, POWER_CTL_ADDR,
-        accel_configs::{self, Alignment, POWER_CTL} 
+        FIFO_CTL_ADDR, FIFO_STATUS_ADDR, FIFO_SAMPLES_MAX, FIFO_ENTRIES_MAX, THRESH_TAP_ADDR, DUR_ADDR, LATENT_ADDR, WINDOW_ADDR,
+        TAP_AXES_ADDR, ACT_TAP_STATUS_ADDR, INT_ENABLE_ADDR, INT_SOURCE_ADDR, THRESH_ACT_ADDR, THRESH_INACT_ADDR,
+        TIME_INACT_ADDR, ACT_INACT_CTL_ADDR, THRESH_FF_ADDR, TIME_FF_ADDR, INT_MAP_ADDR, OFSX_ADDR, OFSY_ADDR, OFSZ_ADDR,
+        shadow::{RegisterShadow, VERIFY_BURST_LEN, VERIFY_BURSTS},
+        dump::{RegisterDump, DUMP_LEN},
+        accel_configs::{self, Alignment, AccelRange, OutputDataRate, FullRes, POWER_CTL, SLEEP_MODE_ODR, FIFO_STATUS, FIFOMode, InterruptPin, ACT_TAP_STATUS, INT_SOURCE} 
+    },
+    utils::{
+        settings::ADXL343Settings, 
+        tap::{TapConfig, TapEvent}, 
+        activity::{ActivityConfig, ActivityStatus},
+        free_fall::FreeFallConfig,
+        interrupts::{InterruptSet, Events},
+        offsets::{CalibrationOrientation, offsets_to_reg_values, reg_values_to_offsets},
+        self_test::SelfTestReport,
+        validation::ConfigIssue
     },
-    utils::settings::ADXL343Settings,
 };
 use core::fmt::Debug;
 
 use core::{error::Error, fmt::{Display, Pointer}};
 
-/// Device Interface
-pub struct ADXL343Interface<I>
+/// Device Interface, over I2c (`ADXL343Interface<I2cBus<I>>`) or SPI (`ADXL343Interface<SpiBus<S>>`)
+pub struct ADXL343Interface<B>
 where
-    I: I2c,
+    B: RegisterBus,
 {
-    i2c: I,
+    bus: B,
     settings: ADXL343Settings,
+    shadow: RegisterShadow,
+    verify_writes: bool,
+    //flags cleared by a read of INT_SOURCE that have not been returned to the caller yet
+    pending_events: Events,
 }
 
-
-impl<I> ADXL343Interface<I>
+impl<I> ADXL343Interface<I2cBus<I>>
 where
     I: I2c,
 {
-    /// Returns uninitialized device object with default settings
+    /// Returns uninitialized device object with default settings, communicating over I2c with the
+    /// device at the primary address 0x53 (ALT ADDRESS grounded)
     pub fn new(i2c: I) -> Self {
+        Self::new_with_bus(I2cBus::new(i2c))
+    }
+
+    /// Returns uninitialized device object with default settings, communicating over I2c with the 
+    /// device at the given address (use I2cAddress::Alternate, 0x1D, when ALT ADDRESS is high)
+    pub fn new_with_address(i2c: I, address: I2cAddress) -> Self {
+        Self::new_with_bus(I2cBus::new_with_address(i2c, address))
+    }
+
+    /// returns both the i2c bus adapter and the settings struct
+    pub fn destroy(mut self) -> (I, ADXL343Settings) {
+        let _ = self.turn_off_measurements();
+        (self.bus.release(), self.settings)
+    }
+
+    /// Same as init, but first validates the settings for the I2c bus running at bus_speed_hz, returning 
+    /// InvalidConfiguration with the first error found (warnings are ignored), see ADXL343Settings::validate.
+    /// Only available on I2c, as the bus bandwidth check counts I2c framing bits
+    pub fn init_validated(&mut self, bus_speed_hz: u32) -> Result<(), ADXL343Error<I::Error>> {
+        if let Some(issue) = self.settings.validate(bus_speed_hz).errors().next() {
+            return Err(ADXL343Error::InvalidConfiguration(*issue));
+        }
+        self.init()
+    }
+}
+
+impl<S> ADXL343Interface<SpiBus<S>>
+where
+    S: SpiDevice,
+{
+    /// Returns uninitialized device object with default settings, communicating over 4-wire SPI
+    pub fn new_spi(spi: S) -> Self {
+        Self::new_with_bus(SpiBus::new(spi))
+    }
+
+    /// Returns uninitialized device object with default settings, communicating over 3-wire SPI.
+    /// The device is switched into 3-wire mode by init
+    pub fn new_spi_3wire(spi: S) -> Self {
+        Self::new_with_bus(SpiBus::new_3wire(spi))
+    }
+
+    /// returns both the spi device and the settings struct
+    pub fn destroy(mut self) -> (S, ADXL343Settings) {
+        let _ = self.turn_off_measurements();
+        (self.bus.release(), self.settings)
+    }
+}
+
+impl<B> ADXL343Interface<B>
+where
+    B: RegisterBus,
+{
+    /// Returns uninitialized device object with default settings, communicating over bus
+    pub fn new_with_bus(bus: B) -> Self {
+        let mut settings = ADXL343Settings::default();
+        settings.set_spi_3wire(bus.three_wire_spi());
         Self {
-            i2c,
-            settings: Default::default()
+            bus,
+            settings,
+            shadow: RegisterShadow::new(),
+            verify_writes: false,
+            pending_events: Events::default()
         }
     }
 
-    /// Returns uninitialized device object with provided settings
-    pub fn with_settings(&mut self, settings: ADXL343Settings) -> Result<(), ADXL343Error<I::Error>>{
+    /// Returns uninitialized device object with provided settings. Returns MeasurementModeBeforeConfig 
+    /// when the settings are in measurement mode (the SPI bit of DATA_FORMAT follows the bus, not the 
+    /// provided settings)
+    pub fn with_settings(&mut self, settings: ADXL343Settings) -> Result<(), ADXL343Error<B::Error>>{
+        self.settings = settings.checked_for_bus(self.bus.three_wire_spi())?;
+        Ok(())
+    }
+
+    /// Returns the settings the device has been configured with
+    pub fn settings(&self) -> &ADXL343Settings {
+        &self.settings
+    }
+
+    /// Last value written to each writable register
+    pub fn shadow(&self) -> &RegisterShadow {
+        &self.shadow
+    }
 
-        //prevents entering into measurement mode before the configs are specified
-        if settings.in_measurement_mode(){
-            return Err(ADXL343Error::MeasurementModeBeforeConfig);
+    /// When enabled, every register write is read back and a differing value is reported as 
+    /// RegisterMismatch. Costs one extra register read per write
+    pub fn set_verify_writes(&mut self, enabled: bool) {
+        self.verify_writes = enabled;
+    }
+
+    pub fn get_verify_writes(&self) -> bool {
+        self.verify_writes
+    }
+
+    /// Reads back every register the driver has written and compares it with the shadow, returning
+    /// RegisterMismatch for the first register that differs (e.g. the device reset to its defaults 
+    /// after a brown-out, in which case init restores the configuration). INT_SOURCE is not read, 
+    /// so pending tap, activity and free fall events are kept
+    pub fn verify_configuration(&mut self) -> Result<(), ADXL343Error<B::Error>>{
+        let read_back = self.read_config_registers()?;
+        if let Some((address, expected, found)) = self.shadow.first_mismatch(&read_back) {
+            return Err(ADXL343Error::RegisterMismatch { address, expected, found });
+        }
+        //FIFO_CTL is read on its own, as the bursts above stop before the data registers
+        if let Some(expected) = self.shadow.get(FIFO_CTL_ADDR) {
+            let found = self.read_register(FIFO_CTL_ADDR)?;
+            if found != expected {
+                return Err(ADXL343Error::RegisterMismatch { address: FIFO_CTL_ADDR, expected, found });
+            }
         }
-        self.settings = settings;
         Ok(())
     }
 
-    /// Initializes the DATA_FORMAT register and BW_RATE registers with the configs located in 
-    /// the settings field (type ADXL343Settings). Will not place the device in measurement mode
-    pub fn init(&mut self) -> Result<(), ADXL343Error<I::Error>> {
-        self.write_to_register(BW_RATE_ADDR, self.settings.BW_RATE_reg_value())?;
-        self.write_to_register(DATA_FORMAT_ADDR, self.settings.DATA_FORMAT_reg_value())?;
+    /// reads THRESH_TAP - DATA_FORMAT in the VERIFY_BURSTS, leaving ACT_TAP_STATUS and INT_SOURCE at 0
+    fn read_config_registers(&mut self) -> Result<[u8; VERIFY_BURST_LEN], ADXL343Error<B::Error>>{
+        let mut registers = [0u8; VERIFY_BURST_LEN];
+        for (first, last) in VERIFY_BURSTS {
+            let start = (first - THRESH_TAP_ADDR) as usize;
+            let end = (last - THRESH_TAP_ADDR) as usize + 1;
+            self.bus.read_registers(first, &mut registers[start..end])?;
+        }
+        Ok(registers)
+    }
+
+    /// Reads the configuration registers back from the device and adopts the decoded settings (see 
+    /// ADXL343Settings::from_register_values), e.g. when attaching to a device configured by a 
+    /// bootloader. The SPI bit follows the bus, as in with_settings. Also records the values read 
+    /// in the register shadow. INT_SOURCE is not read, so pending events are kept
+    pub fn read_settings(&mut self) -> Result<ADXL343Settings, ADXL343Error<B::Error>>{
+        let registers = self.read_config_registers()?;
+        let fifo_ctl = self.read_register(FIFO_CTL_ADDR)?;
+
+        let mut settings = ADXL343Settings::from_register_values(|address| match address {
+            FIFO_CTL_ADDR => fifo_ctl,
+            _ => registers[(address - THRESH_TAP_ADDR) as usize]
+        });
+        settings.set_spi_3wire(self.bus.three_wire_spi());
+
+        for (offset, value) in registers.iter().enumerate() {
+            self.shadow.record(THRESH_TAP_ADDR + offset as u8, *value);
+        }
+        self.shadow.record(FIFO_CTL_ADDR, fifo_ctl);
+        self.settings = settings;
+        Ok(settings)
+    }
+
+    /// Initializes the DATA_FORMAT, BW_RATE and FIFO_CTL registers (and the tap, activity and free fall 
+    /// registers, when their configs are present) with the configs located in the settings field (type ADXL343Settings). 
+    /// INT_MAP and INT_ENABLE are written last, so no interrupt is enabled before its function is configured.
+    /// Will not place the device in measurement mode. Returns LowPowerOdrUnsupported when low power mode
+    /// is enabled at an ODR outside of 12.5 Hz - 400 Hz
+    pub fn init(&mut self) -> Result<(), ADXL343Error<B::Error>> {
+        let writes = self.settings.checked_init_writes()?;
+        for (address, value) in writes.iter() {
+            self.write_to_register(address, value)?;
+        }
         Ok(())
     }
 
     /// Ensures that the device responding to the device address 0xE5 has DEVID 0xE5 
-    pub fn confirm_device(&mut self) -> Result<(), ADXL343Error<I::Error>>{
+    pub fn confirm_device(&mut self) -> Result<(), ADXL343Error<B::Error>>{
 
 	let returned_value = self.read_register(DEVID_ADDR)?;
 		match returned_value{
@@ -67,126 +219,741 @@ where
 
     /// toggles measurement bit to 1 in the POWER_CTL register to begin measurements
     /// does nothing in the event that measurement mode is already enabled
-    pub fn begin_measurements(&mut self) -> Result<(), ADXL343Error<I::Error>>{
+    pub fn begin_measurements(&mut self) -> Result<(), ADXL343Error<B::Error>>{
         if (!self.settings.in_measurement_mode()){
             self.settings.toggle_measurement_mode();
-            self.write_to_register(
-                POWER_CTL_ADDR,
-                POWER_CTL::default().with_measure(0x1).into_bytes()[0]
-            )?;
+            self.write_to_register(POWER_CTL_ADDR, self.settings.POWER_CTL_reg_value())?;
         }
         Ok(())
     }
 
     /// opposite of begin_measurements method
-    pub fn turn_off_measurements(&mut self) ->  Result<(), ADXL343Error<I::Error>>{
+    pub fn turn_off_measurements(&mut self) ->  Result<(), ADXL343Error<B::Error>>{
         if (self.settings.in_measurement_mode()){
             self.settings.toggle_measurement_mode();
-            self.write_to_register(
-                POWER_CTL_ADDR,
-                POWER_CTL::default().with_measure(0x0).into_bytes()[0]
-            )?;
+            self.write_to_register(POWER_CTL_ADDR, self.settings.POWER_CTL_reg_value())?;
         }
         Ok(())
     }
 
+    /// Enables or disables low power mode (LOW_POWER bit of BW_RATE). Returns LowPowerOdrUnsupported,
+    /// leaving the settings unchanged, when enabling it at an ODR outside of 12.5 Hz - 400 Hz
+    pub fn set_low_power_mode(&mut self, enabled: bool) -> Result<(), ADXL343Error<B::Error>>{
+        let mut settings = self.settings;
+        settings.set_low_power_mode(enabled);
+        if !settings.low_power_odr_supported() {
+            return Err(ADXL343Error::LowPowerOdrUnsupported);
+        }
+        self.settings = settings;
+        self.write_to_register(BW_RATE_ADDR, self.settings.BW_RATE_reg_value())
+    }
+
+    /// Places the device into sleep mode, sampling at the wakeup rate with the DATA_READY, 
+    /// WATERMARK and OVERRUN functions suspended
+    pub fn enter_sleep(&mut self) -> Result<(), ADXL343Error<B::Error>>{
+        self.settings.set_sleep(true);
+        self.write_to_register(POWER_CTL_ADDR, self.settings.POWER_CTL_reg_value())
+    }
+
+    /// Returns the device to normal operation. When measuring, the device passes through standby 
+    /// as the datasheet recommends when clearing the sleep bit
+    pub fn leave_sleep(&mut self) -> Result<(), ADXL343Error<B::Error>>{
+        self.settings.set_sleep(false);
+        self.write_power_ctl_via_standby()
+    }
+
+    /// Sets the frequency of readings in sleep mode
+    pub fn set_wakeup_rate(&mut self, wakeup_rate: SLEEP_MODE_ODR) -> Result<(), ADXL343Error<B::Error>>{
+        self.settings.set_wakeup_rate(wakeup_rate);
+        self.write_to_register(POWER_CTL_ADDR, self.settings.POWER_CTL_reg_value())
+    }
+
+    /// Sets or clears the link bit. With link set, the activity function waits for inactivity 
+    /// to be detected (and vice versa), and an activity event clears the sleep state
+    pub fn set_link_mode(&mut self, link: bool) -> Result<(), ADXL343Error<B::Error>>{
+        self.settings.set_link(link);
+        match link {
+            true => self.write_to_register(POWER_CTL_ADDR, self.settings.POWER_CTL_reg_value()),
+            false => self.write_power_ctl_via_standby()
+        }
+    }
+
+    /// Enables auto-sleep (setting link as required): the device sleeps once inactivity is detected
+    /// and wakes on activity. The activity/inactivity function must be configured first 
+    /// (configure_activity), or ActivityNotConfigured is returned. Disabling clears only the 
+    /// AUTO_SLEEP bit, passing through standby as the datasheet recommends
+    pub fn set_auto_sleep(&mut self, enabled: bool) -> Result<(), ADXL343Error<B::Error>>{
+        if enabled {
+            if self.settings.get_activity_config().is_none() {
+                return Err(ADXL343Error::ActivityNotConfigured);
+            }
+            self.settings.set_link(true);
+            self.settings.set_autosleep(true);
+            self.write_to_register(POWER_CTL_ADDR, self.settings.POWER_CTL_reg_value())
+        } else {
+            self.settings.set_autosleep(false);
+            self.write_power_ctl_via_standby()
+        }
+    }
+
+    /// writes POWER_CTL with the measure bit cleared before the final value, so a measuring device 
+    /// passes through standby (required when clearing the sleep, AUTO_SLEEP or link bits)
+    fn write_power_ctl_via_standby(&mut self) -> Result<(), ADXL343Error<B::Error>>{
+        if self.settings.in_measurement_mode() {
+            let standby = POWER_CTL::from_bytes([self.settings.POWER_CTL_reg_value()]).with_measure(0x0);
+            self.write_to_register(POWER_CTL_ADDR, standby.into_bytes()[0])?;
+        }
+        self.write_to_register(POWER_CTL_ADDR, self.settings.POWER_CTL_reg_value())
+    }
+
 
     /// Returns raw accelerometer readings in the format:
     /// [x_low, x_high, y_low, y_high, z_low, z_high] (called DATA_0 and DATA_1 in the datasheet)
    #[inline]
-    pub fn read_full_sample(&mut self) -> Result<[u8; 6], ADXL343Error<I::Error>> {
+    pub fn read_full_sample(&mut self) -> Result<[u8; 6], ADXL343Error<B::Error>> {
         let mut read_buff = [0u8; 6];
-        self.i2c.write_read(ADXL343_ADDR, &[DATAX0_ADDR], &mut read_buff)?;
+        self.bus.read_registers(DATAX0_ADDR, &mut read_buff)?;
         Ok(read_buff)
     }
 
     
-    /// converts accel_data (represented as [lowbits, highbits]) into equivalent i16 representation
+    /// converts accel_data (represented as [lowbits, highbits]) into equivalent i16 representation,
+    /// see ADXL343Settings::axis_value_raw
     #[inline]
-    pub fn axis_value_raw(&mut self, accel_data: [u8; 2] ) -> i16{
-        let mut axis_value = ((accel_data[1] as u16) << REGISTER_SIZE) | accel_data[0] as u16;
-        let shift = 16 - self.settings.resolution_to_bits(); 
-    
-        //change right aligned reading into a left aligned reading
-        if self.settings.get_justification() == Alignment::right {
-            axis_value = axis_value << 6;
-        };
-        (axis_value as i16) >> shift
+    pub fn axis_value_raw(&self, accel_data: [u8; 2] ) -> i16{
+        self.settings.axis_value_raw(accel_data)
     }
 
     /// converts accel_data into its equivalent f32 representation
     #[inline]
-    pub fn axis_value(&mut self, accel_data: i16) -> f32{
+    pub fn axis_value(&self, accel_data: i16) -> f32{
         (accel_data as f32) * self.settings.g_per_lsb()
     }
 
     /// accel reading [x_axis, y_axis, z_axis]
-    pub fn read_accel(&mut self) -> Result<[f32; 3], ADXL343Error<I::Error>>{
-        let binding = self.read_full_sample()?;
-        let (axis_samples,_) = binding.as_chunks::<2>();
-        let x_raw = self.axis_value_raw(axis_samples[0]);
-        let y_raw = self.axis_value_raw(axis_samples[1]);
-        let z_raw = self.axis_value_raw(axis_samples[2]);
-        
-        Ok(
-            [
-                self.axis_value(x_raw), 
-                self.axis_value(y_raw), 
-                self.axis_value(z_raw)
-            ]
-        )
+    pub fn read_accel(&mut self) -> Result<[f32; 3], ADXL343Error<B::Error>>{
+        let sample = self.read_full_sample()?;
+        Ok(self.settings.raw_axes_to_g(self.settings.sample_to_raw_axes(sample)))
+    }
+
+    /// accel reading [x_axis, y_axis, z_axis] in milli-g's, computed with integer math 
+    /// (see ADXL343Settings::raw_axes_to_mg)
+    pub fn read_accel_mg(&mut self) -> Result<[i32; 3], ADXL343Error<B::Error>>{
+        let sample = self.read_full_sample()?;
+        Ok(self.settings.raw_axes_to_mg(self.settings.sample_to_raw_axes(sample)))
+    }
+
+    /// accel reading [x_axis, y_axis, z_axis] in g's as I16F16 fixed point numbers (requires the 
+    /// `fixed` feature)
+    #[cfg(feature = "fixed")]
+    pub fn read_accel_fixed(&mut self) -> Result<[fixed::types::I16F16; 3], ADXL343Error<B::Error>>{
+        let sample = self.read_full_sample()?;
+        Ok(self.settings.raw_axes_to_fixed(self.settings.sample_to_raw_axes(sample)))
+    }
+
+    /// Selects the FIFO mode, the samples field (watermark in FIFO mode, samples retained before the 
+    /// trigger event in trigger mode) and the interrupt line the trigger event is linked to.
+    /// Writes FIFO_CTL immediately and stores the configuration in the settings field
+    pub fn configure_fifo(&mut self, fifo_mode: FIFOMode, samples: u8, trigger: InterruptPin)
+    -> Result<(), ADXL343Error<B::Error>>{
+        if samples > FIFO_SAMPLES_MAX {
+            return Err(ADXL343Error::ValueOutOfRange);
+        }
+        self.settings.set_fifo_mode(fifo_mode);
+        self.settings.set_fifo_samples(samples);
+        self.settings.set_fifo_trigger(trigger);
+        self.write_to_register(FIFO_CTL_ADDR, self.settings.FIFO_CTL_reg_value())
+    }
+
+    /// Returns the FIFO_STATUS register (number of stored entries and the FIFO_TRIG bit)
+    pub fn read_fifo_status(&mut self) -> Result<FIFO_STATUS, ADXL343Error<B::Error>>{
+        Ok(FIFO_STATUS::from_bytes([self.read_register(FIFO_STATUS_ADDR)?]))
+    }
+
+    /// Returns the number of samples currently stored in the FIFO
+    pub fn fifo_entries(&mut self) -> Result<u8, ADXL343Error<B::Error>>{
+        Ok(self.read_fifo_status()?.entries())
+    }
+
+    /// Reads up to buffer.len() FIFO entries as raw samples [x_low, x_high, ..., z_high], in as few 
+    /// bus transactions as the device allows (see RegisterBus::read_fifo_entries). FIFO_STATUS is 
+    /// read first, and only the entries it reports are read. Returns the number of valid entries
+    pub fn read_fifo_samples(&mut self, buffer: &mut [[u8; 6]]) -> Result<usize, ADXL343Error<B::Error>>{
+        let count = buffer.len().min(self.fifo_entries()? as usize);
+        self.bus.read_fifo_entries(&mut buffer[..count])?;
+        Ok(count)
+    }
+
+    /// Drains up to buffer.len() samples from the FIFO into buffer, as i16 axis readings 
+    /// [x_axis, y_axis, z_axis]. Returns the number of samples written to the buffer.
+    /// Each sample read from DATAX0 - DATAZ1 pops one FIFO entry
+    pub fn read_fifo_raw(&mut self, buffer: &mut [[i16; 3]]) -> Result<usize, ADXL343Error<B::Error>>{
+        let mut samples = [[0u8; 6]; FIFO_ENTRIES_MAX];
+        let count = self.read_fifo_samples(&mut samples[..buffer.len().min(FIFO_ENTRIES_MAX)])?;
+        for (axes, sample) in buffer.iter_mut().zip(&samples[..count]) {
+            *axes = self.settings.sample_to_raw_axes(*sample);
+        }
+        Ok(count)
+    }
+
+    /// Same as read_fifo_raw, but every sample is converted into g's
+    pub fn read_fifo_accel(&mut self, buffer: &mut [[f32; 3]]) -> Result<usize, ADXL343Error<B::Error>>{
+        let mut samples = [[0u8; 6]; FIFO_ENTRIES_MAX];
+        let count = self.read_fifo_samples(&mut samples[..buffer.len().min(FIFO_ENTRIES_MAX)])?;
+        for (accel, sample) in buffer.iter_mut().zip(&samples[..count]) {
+            *accel = self.settings.raw_axes_to_g(self.settings.sample_to_raw_axes(*sample));
+        }
+        Ok(count)
+    }
+
+    /// Writes the tap thresholds, timings and axes, then enables the SINGLE_TAP and/or DOUBLE_TAP 
+    /// interrupts. The configuration is stored in the settings field so init reapplies it
+    pub fn configure_tap(&mut self, config: TapConfig) -> Result<(), ADXL343Error<B::Error>>{
+        let writes = config.register_writes().ok_or(ADXL343Error::ValueOutOfRange)?;
+        for (address, value) in writes {
+            self.write_to_register(address, value)?;
+        }
+        self.settings.set_tap_config(Some(config));
+        self.write_to_register(INT_ENABLE_ADDR, self.settings.INT_ENABLE_reg_value())
+    }
+
+    /// Returns the tap event reported in INT_SOURCE (if any) and the first axis involved in it.
+    /// Only the tap flags are consumed: the activity, inactivity and free fall flags cleared by the 
+    /// read of INT_SOURCE are kept for read_events, read_activity_status and read_free_fall
+    pub fn read_tap_event(&mut self) -> Result<Option<TapEvent>, ADXL343Error<B::Error>>{
+        let events = self.latch_events()?;
+        self.pending_events.source.remove(InterruptSet::SINGLE_TAP | InterruptSet::DOUBLE_TAP);
+        Ok(events.tap_event())
+    }
+
+    /// Writes the activity/inactivity thresholds, inactivity time, axes and coupling, then enables 
+    /// the ACTIVITY and/or INACTIVITY interrupts. The configuration is stored in the settings field 
+    /// so init reapplies it
+    pub fn configure_activity(&mut self, config: ActivityConfig) -> Result<(), ADXL343Error<B::Error>>{
+        let writes = config.register_writes().ok_or(ADXL343Error::ValueOutOfRange)?;
+        for (address, value) in writes {
+            self.write_to_register(address, value)?;
+        }
+        self.settings.set_activity_config(Some(config));
+        self.write_to_register(INT_ENABLE_ADDR, self.settings.INT_ENABLE_reg_value())
+    }
+
+    /// Returns the activity/inactivity flags, the activity source axes and the asleep bit.
+    /// Only the activity and inactivity flags are consumed, see read_tap_event
+    pub fn read_activity_status(&mut self) -> Result<ActivityStatus, ADXL343Error<B::Error>>{
+        let events = self.latch_events()?;
+        self.pending_events.source.remove(InterruptSet::ACTIVITY | InterruptSet::INACTIVITY);
+        Ok(events.activity_status())
+    }
+
+    /// Writes the free fall threshold and time, then enables the FREE_FALL interrupt. Returns 
+    /// ValueOutOfRange for a threshold outside 312.5 mg - 562.5 mg or a time outside 100 ms - 350 ms 
+    /// (the datasheet's recommended register values). The configuration is stored in the settings field so 
+    /// init reapplies it
+    pub fn configure_free_fall(&mut self, config: FreeFallConfig) -> Result<(), ADXL343Error<B::Error>>{
+        let writes = config.register_writes().ok_or(ADXL343Error::ValueOutOfRange)?;
+        for (address, value) in writes {
+            self.write_to_register(address, value)?;
+        }
+        self.settings.set_free_fall_config(Some(config));
+        self.write_to_register(INT_ENABLE_ADDR, self.settings.INT_ENABLE_reg_value())
+    }
+
+    /// Returns true if a free fall event has occurred since it was last reported. Only the free fall 
+    /// flag is consumed, see read_tap_event
+    pub fn read_free_fall(&mut self) -> Result<bool, ADXL343Error<B::Error>>{
+        let events = self.latch_events()?;
+        self.pending_events.source.remove(InterruptSet::FREE_FALL);
+        Ok(events.free_fall())
+    }
+
+    /// Reads ACT_TAP_STATUS, then INT_SOURCE once, and returns every event: the flags set in 
+    /// INT_SOURCE, along with the tap, activity, inactivity and free fall flags cleared by earlier 
+    /// reads of INT_SOURCE (e.g. by read_tap_event) that have not been reported yet
+    pub fn read_events(&mut self) -> Result<Events, ADXL343Error<B::Error>>{
+        let events = self.latch_events()?;
+        self.pending_events = Events::default();
+        Ok(events)
+    }
+
+    /// reads ACT_TAP_STATUS and INT_SOURCE, keeping the flags cleared by the read in pending_events. 
+    /// Returns the pending events along with the flags that are not cleared by the read
+    fn latch_events(&mut self) -> Result<Events, ADXL343Error<B::Error>>{
+        self.pending_events.act_tap_status = self.read_register(ACT_TAP_STATUS_ADDR)?;
+        let source = self.latch_interrupt_source()?;
+        Ok(Events::new(self.pending_events.source | source, self.pending_events.act_tap_status))
+    }
+
+    /// reads INT_SOURCE, keeping the flags cleared by the read in pending_events
+    fn latch_interrupt_source(&mut self) -> Result<InterruptSet, ADXL343Error<B::Error>>{
+        let source = InterruptSet::from_bits(self.read_register(INT_SOURCE_ADDR)?);
+        self.pending_events.source |= source & Events::CLEARED_ON_READ;
+        Ok(source)
+    }
+
+    /// Enables every interrupt in interrupts (the others keep their current state) and writes INT_ENABLE.
+    /// Route the interrupts with map_interrupts before enabling them
+    pub fn enable_interrupts(&mut self, interrupts: InterruptSet) -> Result<(), ADXL343Error<B::Error>>{
+        self.settings.set_interrupts_enabled(interrupts, true);
+        self.write_to_register(INT_ENABLE_ADDR, self.settings.INT_ENABLE_reg_value())
+    }
+
+    /// Disables every interrupt in interrupts (the others keep their current state) and writes INT_ENABLE
+    pub fn disable_interrupts(&mut self, interrupts: InterruptSet) -> Result<(), ADXL343Error<B::Error>>{
+        self.settings.set_interrupts_enabled(interrupts, false);
+        self.write_to_register(INT_ENABLE_ADDR, self.settings.INT_ENABLE_reg_value())
+    }
+
+    /// Routes every interrupt in interrupts to the INT1 or INT2 pin and writes INT_MAP
+    pub fn map_interrupts(&mut self, interrupts: InterruptSet, pin: InterruptPin) -> Result<(), ADXL343Error<B::Error>>{
+        self.settings.map_interrupts(interrupts, pin);
+        self.write_to_register(INT_MAP_ADDR, self.settings.INT_MAP_reg_value())
+    }
+
+    /// Returns the functions which have triggered an event (INT_SOURCE), including the tap, activity, 
+    /// inactivity and free fall events not reported yet (see read_events).
+    /// 
+    /// Reading INT_SOURCE clears the SINGLE_TAP, DOUBLE_TAP, ACTIVITY, INACTIVITY and FREE_FALL flags, 
+    /// so they are only returned once. DATA_READY, WATERMARK and OVERRUN are not cleared by this read, 
+    /// they clear once enough data has been read from DATAX0 - DATAZ1 (i.e. the FIFO is drained below 
+    /// the watermark, or no new sample is left unread)
+    pub fn read_interrupt_source(&mut self) -> Result<InterruptSet, ADXL343Error<B::Error>>{
+        let source = self.latch_interrupt_source()?;
+        let pending = self.pending_events.source;
+        self.pending_events.source = InterruptSet::empty();
+        Ok(source | pending)
+    }
+
+    /// Writes per-axis offsets (in g's, 15.6 mg/LSB) to OFSX, OFSY and OFSZ. The offsets are added to 
+    /// every following sample. Returns ValueOutOfRange for offsets outside of -2 g - 1.98 g
+    pub fn set_offsets(&mut self, offsets_g: [f32; 3]) -> Result<(), ADXL343Error<B::Error>>{
+        let offsets = offsets_to_reg_values(offsets_g).ok_or(ADXL343Error::ValueOutOfRange)?;
+        self.write_offsets(offsets)
+    }
+
+    /// Reads OFSX, OFSY and OFSZ back, in g's
+    pub fn read_offsets(&mut self) -> Result<[f32; 3], ADXL343Error<B::Error>>{
+        let mut read_buff = [0u8; 3];
+        self.bus.read_registers(OFSX_ADDR, &mut read_buff)?;
+        Ok(reg_values_to_offsets(read_buff.map(|value| value as i8)))
+    }
+
+    /// Calibrates the offset registers so that, held in orientation, the device reads the ideal 
+    /// +/-1 g on the vertical axis and 0 g on the others. Clears the current offsets, averages 
+    /// `samples` readings (each one waited for through the DATA_READY bit of INT_SOURCE, keeping the 
+    /// event flags its read clears for read_events), then programs and returns the offsets in g's.
+    /// On error (e.g. Timeout) the previous offsets are written back.
+    /// Measurement mode must be on and the device kept still throughout
+    pub fn calibrate_offsets(&mut self, orientation: CalibrationOrientation, samples: usize) 
+    -> Result<[f32; 3], ADXL343Error<B::Error>>{
+        if !self.settings.in_measurement_mode() {
+            return Err(ADXL343Error::NotInMeasurementMode);
+        }
+        let original = self.settings.get_offsets();
+        match self.measure_offsets(orientation, samples) {
+            Ok(offsets) => {
+                self.write_offsets(offsets)?;
+                Ok(reg_values_to_offsets(offsets))
+            },
+            Err(error) => {
+                let _ = self.write_offsets(original);
+                Err(error)
+            }
+        }
+    }
+
+    /// OFSX, OFSY and OFSZ register values calibrating the device in orientation, measured with 
+    /// the offsets cleared
+    fn measure_offsets(&mut self, orientation: CalibrationOrientation, samples: usize) 
+    -> Result<[i8; 3], ADXL343Error<B::Error>>{
+        self.write_offsets([0; 3])?;
+        let average = self.average_accel(samples)?;
+        let expected = orientation.expected_accel();
+        let offsets_g = [
+            expected[0] - average[0],
+            expected[1] - average[1],
+            expected[2] - average[2]
+        ];
+        offsets_to_reg_values(offsets_g).ok_or(ADXL343Error::ValueOutOfRange)
+    }
+
+    /// Runs the built-in self-test: averages `samples` readings with the self-test force off, turns 
+    /// it on (discarding the samples taken while the output settles), averages `samples` more and 
+    /// compares the per-axis change to the datasheet limits scaled for supply_voltage (2.0 V - 3.6 V).
+    /// The test runs at +/-16g full resolution and 100 Hz in normal power mode, as the datasheet 
+    /// requires, and the original DATA_FORMAT (with the self-test force off) and BW_RATE are restored 
+    /// afterwards, also when the test fails with an error such as Timeout. Measurement mode must be 
+    /// on and the device kept still throughout
+    pub fn run_self_test(&mut self, supply_voltage: f32, samples: usize) 
+    -> Result<SelfTestReport, ADXL343Error<B::Error>>{
+        if !self.settings.in_measurement_mode() {
+            return Err(ADXL343Error::NotInMeasurementMode);
+        }
+        //validate the supply voltage before touching the device
+        SelfTestReport::new([0.0; 3], supply_voltage).ok_or(ADXL343Error::ValueOutOfRange)?;
+
+        let original = self.settings;
+        let measured = self.measure_self_test_deltas(samples);
+        self.settings = original;
+        let restored_format = self.write_to_register(DATA_FORMAT_ADDR, self.settings.DATA_FORMAT_reg_value());
+        let restored_rate = self.write_to_register(BW_RATE_ADDR, self.settings.BW_RATE_reg_value());
+
+        let deltas_g = measured?;
+        restored_format?;
+        restored_rate?;
+        SelfTestReport::new(deltas_g, supply_voltage).ok_or(ADXL343Error::ValueOutOfRange)
+    }
+
+    /// number of samples discarded after the self-test force is applied, while the output settles
+    const SELF_TEST_SETTLING_SAMPLES: usize = 4;
+
+    fn measure_self_test_deltas(&mut self, samples: usize) -> Result<[f32; 3], ADXL343Error<B::Error>>{
+        //the datasheet limits hold in normal power mode at 100 Hz - 800 Hz or 3200 Hz
+        self.settings.set_low_power_mode(false);
+        self.settings.set_odr(OutputDataRate::Hz100);
+        self.write_to_register(BW_RATE_ADDR, self.settings.BW_RATE_reg_value())?;
+        self.settings.set_range(AccelRange::_16g);
+        self.settings.set_resolution(FullRes::full_res);
+        self.settings.set_self_test(false);
+        self.write_to_register(DATA_FORMAT_ADDR, self.settings.DATA_FORMAT_reg_value())?;
+        let self_test_off = self.average_accel(samples)?;
+
+        self.settings.set_self_test(true);
+        self.write_to_register(DATA_FORMAT_ADDR, self.settings.DATA_FORMAT_reg_value())?;
+        for _ in 0..Self::SELF_TEST_SETTLING_SAMPLES {
+            self.poll_data_ready()?;
+            self.read_full_sample()?;
+        }
+        let self_test_on = self.average_accel(samples)?;
+
+        Ok([
+            self_test_on[0] - self_test_off[0],
+            self_test_on[1] - self_test_off[1],
+            self_test_on[2] - self_test_off[2]
+        ])
+    }
+
+    fn write_offsets(&mut self, offsets: [i8; 3]) -> Result<(), ADXL343Error<B::Error>>{
+        self.write_to_register(OFSX_ADDR, offsets[0] as u8)?;
+        self.write_to_register(OFSY_ADDR, offsets[1] as u8)?;
+        self.write_to_register(OFSZ_ADDR, offsets[2] as u8)?;
+        self.settings.set_offsets(offsets);
+        Ok(())
+    }
+
+    /// average of `samples` consecutive new readings, in g's
+    fn average_accel(&mut self, samples: usize) -> Result<[f32; 3], ADXL343Error<B::Error>>{
+        if samples == 0 {
+            return Err(ADXL343Error::ValueOutOfRange);
+        }
+        let mut sum = [0f32; 3];
+        for _ in 0..samples {
+            self.poll_data_ready()?;
+            let accel = self.read_accel()?;
+            for (total, axis) in sum.iter_mut().zip(accel) {
+                *total += axis;
+            }
+        }
+        Ok(sum.map(|total| total / samples as f32))
+    }
+
+    /// busy waits on the DATA_READY bit of INT_SOURCE, keeping the event flags cleared by the reads.
+    /// Gives up with Timeout after enough reads to span two sample periods at the clock rate of the 
+    /// bus (see RegisterBus::register_read_ns)
+    fn poll_data_ready(&mut self) -> Result<(), ADXL343Error<B::Error>>{
+        let read_ns = self.bus.register_read_ns().max(1) as u128;
+        let max_polls = 2 * self.settings.get_odr().period().as_nanos() / read_ns;
+        for _ in 0..max_polls {
+            if self.latch_interrupt_source()?.contains(InterruptSet::DATA_READY) {
+                return Ok(());
+            }
+        }
+        Err(ADXL343Error::Timeout)
+    }
+
+    /// Reads registers 0x1D - 0x39 in one burst. The burst includes DATAX0 - DATAZ1, so it pops a 
+    /// FIFO entry (when the FIFO is not bypassed) and clears DATA_READY like any sample read. It also 
+    /// reads INT_SOURCE (0x30), clearing the SINGLE_TAP, DOUBLE_TAP, ACTIVITY, INACTIVITY and FREE_FALL 
+    /// flags: events pending on the device are only visible in the dump, not to read_events
+    pub fn dump_registers(&mut self) -> Result<RegisterDump, ADXL343Error<B::Error>> {
+        let mut values = [0u8; DUMP_LEN];
+        self.bus.read_registers(THRESH_TAP_ADDR, &mut values)?;
+        Ok(RegisterDump::new(values))
     }
 
     #[inline]
-    pub fn read_register(&mut self, reg_address: u8) -> Result<u8, ADXL343Error<I::Error>> {
+    pub fn read_register(&mut self, reg_address: u8) -> Result<u8, ADXL343Error<B::Error>> {
         let mut read_buff = [0u8];
-        self.i2c.write_read(ADXL343_ADDR, &[reg_address], &mut read_buff)?;
+        self.bus.read_registers(reg_address, &mut read_buff)?;
         Ok(read_buff[0])
     }
 
-    fn write_to_register(&mut self, reg_address: u8, value: u8) -> Result<(), ADXL343Error<I::Error>> {
-        self.i2c.write(ADXL343_ADDR, &mut [reg_address, value])?;
+    fn write_to_register(&mut self, reg_address: u8, value: u8) -> Result<(), ADXL343Error<B::Error>> {
+        self.bus.write_register(reg_address, value)?;
+        self.shadow.record(reg_address, value);
+        if self.verify_writes {
+            let found = self.read_register(reg_address)?;
+            if found != value {
+                return Err(ADXL343Error::RegisterMismatch { address: reg_address, expected: value, found });
+            }
+        }
         Ok(())
     }
-    
-    /// returns both the i2c bus adapter and the settings struct
-    pub fn destroy(mut self) -> (I, ADXL343Settings) {
-        self.turn_off_measurements();
-        (self.i2c, self.settings)
-    }
 
 }
 
-//embedded_hal::i2c::I2c::Error has been labeled as I2c_Error
+//E is the error type of the bus (I2c or SPI) the device is attached to
 
 #[derive(Debug)]
-pub enum ADXL343Error<E: I2c_Error>
+pub enum ADXL343Error<E>
 {
     Interface(E),         // error from I2C/SPI interface
     DeviceIdMismatch,     
-    MeasurementModeBeforeConfig
+    MeasurementModeBeforeConfig,
+    ValueOutOfRange,      // configuration value does not fit in its register field
+    NotInMeasurementMode, // operation needs samples, but measurement mode is off
+    ActivityNotConfigured, // auto-sleep requires the activity/inactivity function
+    LowPowerOdrUnsupported, // low power mode requested outside of 12.5 Hz - 400 Hz
+    RegisterMismatch { address: u8, expected: u8, found: u8 }, // register read back differs from the value written
+    InvalidConfiguration(ConfigIssue), // first error found by ADXL343Settings::validate, see init_validated
+    Timeout               // no new sample (DATA_READY) within two sample periods
 }
 
-impl<E: I2c_Error+ Debug> Error for ADXL343Error<E>{}
+impl<E: Debug> Error for ADXL343Error<E>{}
 
-impl<E: I2c_Error+ Debug> Display for ADXL343Error<E> {
+impl<E: Debug> Display for ADXL343Error<E> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
-            ADXL343Error::Interface(i2c_error) => i2c_error.fmt(f),
+            ADXL343Error::Interface(bus_error) => bus_error.fmt(f),
             ADXL343Error::DeviceIdMismatch => {
                 f.write_str("Wrong device ID returned")
             },
             ADXL343Error::MeasurementModeBeforeConfig => {
                 f.write_str("Attempted to turn on measurement mode prior to configuration")
+            },
+            ADXL343Error::ValueOutOfRange => {
+                f.write_str("Configuration value is outside of the range supported by the device")
+            },
+            ADXL343Error::NotInMeasurementMode => {
+                f.write_str("Operation requires measurement mode to be turned on")
+            },
+            ADXL343Error::ActivityNotConfigured => {
+                f.write_str("Auto-sleep requires activity/inactivity detection to be configured")
+            },
+            ADXL343Error::LowPowerOdrUnsupported => {
+                f.write_str("Low power mode is only supported for output data rates of 12.5 Hz to 400 Hz")
+            },
+            ADXL343Error::RegisterMismatch { address, expected, found } => {
+                write!(f, "Register {:#04X} reads {:#04X}, expected {:#04X}", address, found, expected)
+            },
+            ADXL343Error::InvalidConfiguration(issue) => {
+                write!(f, "Invalid configuration: {}", issue)
+            },
+            ADXL343Error::Timeout => {
+                f.write_str("Timed out waiting for a new sample")
             }
         }
     }
 }
 
-impl<E: I2c_Error> From<E> for ADXL343Error<E> {
+impl<E> From<E> for ADXL343Error<E> {
     fn from(value: E) -> Self {
         ADXL343Error::Interface(value)
     }
 }
 
 
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use std::{vec, vec::Vec};
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+    use super::*;
+
+    /// expected reads of read_config_registers, returning registers (indexed from THRESH_TAP)
+    fn config_reads(registers: &[u8]) -> [I2cTransaction; 3] {
+        VERIFY_BURSTS.map(|(first, last)| {
+            let start = (first - THRESH_TAP_ADDR) as usize;
+            let end = (last - THRESH_TAP_ADDR) as usize + 1;
+            I2cTransaction::write_read(ADXL343_ADDR, vec![first], registers[start..end].to_vec())
+        })
+    }
+
+    #[test]
+    fn verify_after_write(){
+        let expectations = [
+            I2cTransaction::write(ADXL343_ADDR, vec![POWER_CTL_ADDR, 0x08]),
+            I2cTransaction::write_read(ADXL343_ADDR, vec![POWER_CTL_ADDR], vec![0x08]),
+            I2cTransaction::write(ADXL343_ADDR, vec![BW_RATE_ADDR, 0x1A]),
+            I2cTransaction::write_read(ADXL343_ADDR, vec![BW_RATE_ADDR], vec![0x0A]),
+            I2cTransaction::write(ADXL343_ADDR, vec![POWER_CTL_ADDR, 0x00]),
+            I2cTransaction::write_read(ADXL343_ADDR, vec![POWER_CTL_ADDR], vec![0x00]),
+        ];
+        let mut sensor = ADXL343Interface::new(I2cMock::new(&expectations));
+        sensor.set_verify_writes(true);
+        sensor.begin_measurements().unwrap();
+        assert!(matches!(
+            sensor.set_low_power_mode(true),
+            Err(ADXL343Error::RegisterMismatch { address: BW_RATE_ADDR, expected: 0x1A, found: 0x0A })
+        ));
+        assert_eq!(sensor.shadow().get(POWER_CTL_ADDR), Some(0x08));
+
+        let (mut i2c, _) = sensor.destroy();
+        i2c.done();
+    }
+
+    #[test]
+    fn verify_configuration_after_reset(){
+        let mut read_back = vec![0u8; VERIFY_BURST_LEN];
+        read_back[(POWER_CTL_ADDR - THRESH_TAP_ADDR) as usize] = 0x08;
+        let mut reset = vec![0u8; VERIFY_BURST_LEN];
+        reset[(BW_RATE_ADDR - THRESH_TAP_ADDR) as usize] = 0x0A;
+        let expectations: Vec<_> = [I2cTransaction::write(ADXL343_ADDR, vec![POWER_CTL_ADDR, 0x08])].into_iter()
+            .chain(config_reads(&read_back))
+            .chain(config_reads(&reset))
+            .chain([I2cTransaction::write(ADXL343_ADDR, vec![POWER_CTL_ADDR, 0x00])])
+            .collect();
+        let mut sensor = ADXL343Interface::new(I2cMock::new(&expectations));
+        sensor.begin_measurements().unwrap();
+        sensor.verify_configuration().unwrap();
+        assert!(matches!(
+            sensor.verify_configuration(),
+            Err(ADXL343Error::RegisterMismatch { address: POWER_CTL_ADDR, expected: 0x08, found: 0x00 })
+        ));
+
+        let (mut i2c, _) = sensor.destroy();
+        i2c.done();
+    }
+
+    #[test]
+    fn adopt_device_settings(){
+        let mut registers = [0u8; VERIFY_BURST_LEN];
+        registers[(BW_RATE_ADDR - THRESH_TAP_ADDR) as usize] = 0x0D;
+        registers[(POWER_CTL_ADDR - THRESH_TAP_ADDR) as usize] = 0x08;
+        registers[(DATA_FORMAT_ADDR - THRESH_TAP_ADDR) as usize] = 0x0B;
+        //the mock fails on any other transaction: ACT_TAP_STATUS (0x2B) and INT_SOURCE (0x30) are not read
+        let offset = |address: u8| (address - THRESH_TAP_ADDR) as usize;
+        let expectations = [
+            I2cTransaction::write_read(ADXL343_ADDR, vec![THRESH_TAP_ADDR], registers[..=offset(TAP_AXES_ADDR)].to_vec()),
+            I2cTransaction::write_read(ADXL343_ADDR, vec![BW_RATE_ADDR], registers[offset(BW_RATE_ADDR)..=offset(INT_MAP_ADDR)].to_vec()),
+            I2cTransaction::write_read(ADXL343_ADDR, vec![DATA_FORMAT_ADDR], vec![0x0B]),
+            I2cTransaction::write_read(ADXL343_ADDR, vec![FIFO_CTL_ADDR], vec![0x9F]),
+            I2cTransaction::write(ADXL343_ADDR, vec![POWER_CTL_ADDR, 0x00]),
+        ];
+        let mut sensor = ADXL343Interface::new(I2cMock::new(&expectations));
+        let settings = sensor.read_settings().unwrap();
+        assert_eq!(settings.get_odr(), OutputDataRate::Hz800);
+        assert_eq!(settings.get_range(), AccelRange::_16g);
+        assert_eq!(settings.get_resolution(), FullRes::full_res);
+        assert!(settings.in_measurement_mode());
+        assert_eq!(settings.get_fifo_mode(), FIFOMode::STREAM);
+        assert_eq!(settings.get_fifo_samples(), 0x1F);
+        assert_eq!(settings.get_tap_config(), None);
+        assert_eq!(sensor.settings(), &settings);
+        assert_eq!(sensor.shadow().get(FIFO_CTL_ADDR), Some(0x9F));
+
+        let (mut i2c, _) = sensor.destroy();
+        i2c.done();
+    }
+
+    #[test]
+    fn data_ready_timeout_follows_bus_clock(){
+        //a 390 us read at 100 kHz: two 312.5 us periods at 3200 Hz only allow one poll
+        let expectations = [
+            I2cTransaction::write(ADXL343_ADDR, vec![POWER_CTL_ADDR, 0x08]),
+            I2cTransaction::write(ADXL343_ADDR, vec![OFSX_ADDR, 0x00]),
+            I2cTransaction::write(ADXL343_ADDR, vec![OFSY_ADDR, 0x00]),
+            I2cTransaction::write(ADXL343_ADDR, vec![OFSZ_ADDR, 0x00]),
+            I2cTransaction::write_read(ADXL343_ADDR, vec![INT_SOURCE_ADDR], vec![0x00]),
+            I2cTransaction::write(ADXL343_ADDR, vec![OFSX_ADDR, 0x02]),
+            I2cTransaction::write(ADXL343_ADDR, vec![OFSY_ADDR, 0x00]),
+            I2cTransaction::write(ADXL343_ADDR, vec![OFSZ_ADDR, 0x00]),
+            I2cTransaction::write(ADXL343_ADDR, vec![POWER_CTL_ADDR, 0x00]),
+        ];
+        let bus = I2cBus::new(I2cMock::new(&expectations)).with_clock_hz(100_000);
+        let mut sensor = ADXL343Interface::new_with_bus(bus);
+        let mut settings = ADXL343Settings::default();
+        settings.set_odr(OutputDataRate::Hz3200);
+        settings.set_offsets([2, 0, 0]);
+        sensor.with_settings(settings).unwrap();
+        sensor.begin_measurements().unwrap();
+        assert!(matches!(sensor.calibrate_offsets(CalibrationOrientation::ZUp, 4), Err(ADXL343Error::Timeout)));
+        assert_eq!(sensor.settings().get_offsets(), [2, 0, 0]);
+
+        let (mut i2c, _) = sensor.destroy();
+        i2c.done();
+    }
+
+    #[test]
+    fn self_test_restores_bw_rate(){
+        use embedded_hal::i2c::ErrorKind;
+        let expectations = [
+            I2cTransaction::write(ADXL343_ADDR, vec![POWER_CTL_ADDR, 0x08]),
+            //normal power at 100 Hz, then +/-16g full resolution
+            I2cTransaction::write(ADXL343_ADDR, vec![BW_RATE_ADDR, 0x0A]),
+            I2cTransaction::write(ADXL343_ADDR, vec![DATA_FORMAT_ADDR, 0x0B]),
+            I2cTransaction::write_read(ADXL343_ADDR, vec![INT_SOURCE_ADDR], vec![0x00]).with_error(ErrorKind::Other),
+            //low power at 200 Hz and the original DATA_FORMAT are restored on the error path
+            I2cTransaction::write(ADXL343_ADDR, vec![DATA_FORMAT_ADDR, 0x00]),
+            I2cTransaction::write(ADXL343_ADDR, vec![BW_RATE_ADDR, 0x1B]),
+            I2cTransaction::write(ADXL343_ADDR, vec![POWER_CTL_ADDR, 0x00]),
+        ];
+        let mut sensor = ADXL343Interface::new(I2cMock::new(&expectations));
+        let mut settings = ADXL343Settings::default();
+        settings.set_odr(OutputDataRate::Hz200);
+        settings.set_low_power_mode(true);
+        sensor.with_settings(settings).unwrap();
+        sensor.begin_measurements().unwrap();
+        assert!(matches!(sensor.run_self_test(2.5, 4), Err(ADXL343Error::Interface(ErrorKind::Other))));
+        assert_eq!(sensor.settings().get_odr(), OutputDataRate::Hz200);
+        assert!(sensor.settings().get_low_power_mode());
+
+        let (mut i2c, _) = sensor.destroy();
+        i2c.done();
+    }
+
+    #[test]
+    fn leave_sleep_via_standby(){
+        let expectations = [
+            I2cTransaction::write(ADXL343_ADDR, vec![POWER_CTL_ADDR, 0x08]),
+            I2cTransaction::write(ADXL343_ADDR, vec![POWER_CTL_ADDR, 0x0C]),
+            I2cTransaction::write(ADXL343_ADDR, vec![POWER_CTL_ADDR, 0x00]),
+            I2cTransaction::write(ADXL343_ADDR, vec![POWER_CTL_ADDR, 0x08]),
+            I2cTransaction::write(ADXL343_ADDR, vec![POWER_CTL_ADDR, 0x00]),
+        ];
+        let mut sensor = ADXL343Interface::new(I2cMock::new(&expectations));
+        sensor.begin_measurements().unwrap();
+        sensor.enter_sleep().unwrap();
+        sensor.leave_sleep().unwrap();
+
+        let (mut i2c, _) = sensor.destroy();
+        i2c.done();
+    }
+
+    #[test]
+    fn auto_sleep_power_ctl_order(){
+        let expectations = [
+            I2cTransaction::write(ADXL343_ADDR, vec![POWER_CTL_ADDR, 0x08]),
+            //link and AUTO_SLEEP are set together, without leaving measurement mode
+            I2cTransaction::write(ADXL343_ADDR, vec![POWER_CTL_ADDR, 0x38]),
+            //clearing AUTO_SLEEP passes through standby, link stays set
+            I2cTransaction::write(ADXL343_ADDR, vec![POWER_CTL_ADDR, 0x20]),
+            I2cTransaction::write(ADXL343_ADDR, vec![POWER_CTL_ADDR, 0x28]),
+            I2cTransaction::write(ADXL343_ADDR, vec![POWER_CTL_ADDR, 0x20]),
+        ];
+        let mut sensor = ADXL343Interface::new(I2cMock::new(&expectations));
+        assert!(matches!(sensor.set_auto_sleep(true), Err(ADXL343Error::ActivityNotConfigured)));
+        assert!(!sensor.settings().get_autosleep());
+
+        let mut settings = ADXL343Settings::default();
+        settings.set_activity_config(Some(ActivityConfig::default()));
+        sensor.with_settings(settings).unwrap();
+        sensor.begin_measurements().unwrap();
+        sensor.set_auto_sleep(true).unwrap();
+        sensor.set_auto_sleep(false).unwrap();
+
+        let (mut i2c, _) = sensor.destroy();
+        i2c.done();
+    }
+}
@@ -0,0 +1,226 @@
+use embedded_hal_async::i2c::I2c;
+use crate::{
+    adxl343_interface::ADXL343Error,
+    bus::I2cAddress,
+    registers::{
+        DEVID_ADDR, DATAX0_ADDR, DEVID_REG_VALUE, POWER_CTL_ADDR, THRESH_TAP_ADDR,
+        dump::{RegisterDump, DUMP_LEN},
+    },
+    utils::settings::ADXL343Settings,
+};
+
+/// Async Device Interface, the `async fn` twin of ADXL343Interface (requires the `async` feature).
+/// Register values are built, and samples decoded, by ADXL343Settings, exactly as in the 
+/// blocking driver
+pub struct ADXL343InterfaceAsync<I>
+where
+    I: I2c,
+{
+    i2c: I,
+    address: u8,
+    settings: ADXL343Settings,
+}
+
+impl<I> ADXL343InterfaceAsync<I>
+where
+    I: I2c,
+{
+    /// Returns uninitialized device object with default settings, at the primary address 0x53
+    pub fn new(i2c: I) -> Self {
+        Self::new_with_address(i2c, I2cAddress::Primary)
+    }
+
+    /// Returns uninitialized device object with default settings, at the given address
+    pub fn new_with_address(i2c: I, address: I2cAddress) -> Self {
+        Self {
+            i2c,
+            address: address.address(),
+            settings: Default::default()
+        }
+    }
+
+    /// Returns uninitialized device object with provided settings, see ADXL343Interface::with_settings
+    pub fn with_settings(&mut self, settings: ADXL343Settings) -> Result<(), ADXL343Error<I::Error>>{
+        self.settings = settings.checked_for_bus(false)?;
+        Ok(())
+    }
+
+    /// Returns the settings the device has been configured with
+    pub fn settings(&self) -> &ADXL343Settings {
+        &self.settings
+    }
+
+    /// Writes the same registers as ADXL343Interface::init. Will not place the device in measurement mode
+    pub async fn init(&mut self) -> Result<(), ADXL343Error<I::Error>> {
+        let writes = self.settings.checked_init_writes()?;
+        for (address, value) in writes.iter() {
+            self.write_to_register(address, value).await?;
+        }
+        Ok(())
+    }
+
+    /// Same as init, but first validates the settings, see ADXL343Interface::init_validated
+    pub async fn init_validated(&mut self, bus_speed_hz: u32) -> Result<(), ADXL343Error<I::Error>> {
+        if let Some(issue) = self.settings.validate(bus_speed_hz).errors().next() {
+            return Err(ADXL343Error::InvalidConfiguration(*issue));
+        }
+        self.init().await
+    }
+
+    /// Ensures that the device responding to the device address 0xE5 has DEVID 0xE5 
+    pub async fn confirm_device(&mut self) -> Result<(), ADXL343Error<I::Error>>{
+        match self.read_register(DEVID_ADDR).await? {
+            DEVID_REG_VALUE => Ok(()),
+            _ => Err(ADXL343Error::DeviceIdMismatch)
+        }
+    }
+
+    /// toggles measurement bit to 1 in the POWER_CTL register to begin measurements
+    /// does nothing in the event that measurement mode is already enabled
+    pub async fn begin_measurements(&mut self) -> Result<(), ADXL343Error<I::Error>>{
+        if !self.settings.in_measurement_mode() {
+            self.settings.toggle_measurement_mode();
+            self.write_to_register(POWER_CTL_ADDR, self.settings.POWER_CTL_reg_value()).await?;
+        }
+        Ok(())
+    }
+
+    /// opposite of begin_measurements method
+    pub async fn turn_off_measurements(&mut self) -> Result<(), ADXL343Error<I::Error>>{
+        if self.settings.in_measurement_mode() {
+            self.settings.toggle_measurement_mode();
+            self.write_to_register(POWER_CTL_ADDR, self.settings.POWER_CTL_reg_value()).await?;
+        }
+        Ok(())
+    }
+
+    /// Returns raw accelerometer readings in the format:
+    /// [x_low, x_high, y_low, y_high, z_low, z_high] (called DATA_0 and DATA_1 in the datasheet)
+    #[inline]
+    pub async fn read_full_sample(&mut self) -> Result<[u8; 6], ADXL343Error<I::Error>> {
+        let mut read_buff = [0u8; 6];
+        self.i2c.write_read(self.address, &[DATAX0_ADDR], &mut read_buff).await?;
+        Ok(read_buff)
+    }
+
+    /// accel reading [x_axis, y_axis, z_axis]
+    pub async fn read_accel(&mut self) -> Result<[f32; 3], ADXL343Error<I::Error>>{
+        let sample = self.read_full_sample().await?;
+        Ok(self.settings.raw_axes_to_g(self.settings.sample_to_raw_axes(sample)))
+    }
+
+    /// accel reading [x_axis, y_axis, z_axis] in milli-g's, computed with integer math
+    pub async fn read_accel_mg(&mut self) -> Result<[i32; 3], ADXL343Error<I::Error>>{
+        let sample = self.read_full_sample().await?;
+        Ok(self.settings.raw_axes_to_mg(self.settings.sample_to_raw_axes(sample)))
+    }
+
+    /// accel reading [x_axis, y_axis, z_axis] in g's as I16F16 fixed point numbers (requires the 
+    /// `fixed` feature)
+    #[cfg(feature = "fixed")]
+    pub async fn read_accel_fixed(&mut self) -> Result<[fixed::types::I16F16; 3], ADXL343Error<I::Error>>{
+        let sample = self.read_full_sample().await?;
+        Ok(self.settings.raw_axes_to_fixed(self.settings.sample_to_raw_axes(sample)))
+    }
+
+    /// Reads registers 0x1D - 0x39 in one burst, see ADXL343Interface::dump_registers. Like any 
+    /// read of INT_SOURCE, the burst clears the tap, activity and free fall flags
+    #[inline]
+    pub async fn dump_registers(&mut self) -> Result<RegisterDump, ADXL343Error<I::Error>> {
+        let mut values = [0u8; DUMP_LEN];
+        self.i2c.write_read(self.address, &[THRESH_TAP_ADDR], &mut values).await?;
+        Ok(RegisterDump::new(values))
+    }
+
+    pub async fn read_register(&mut self, reg_address: u8) -> Result<u8, ADXL343Error<I::Error>> {
+        let mut read_buff = [0u8];
+        self.i2c.write_read(self.address, &[reg_address], &mut read_buff).await?;
+        Ok(read_buff[0])
+    }
+
+    async fn write_to_register(&mut self, reg_address: u8, value: u8) -> Result<(), ADXL343Error<I::Error>> {
+        self.i2c.write(self.address, &[reg_address, value]).await?;
+        Ok(())
+    }
+
+    /// turns off measurement mode, then returns both the i2c bus adapter and the settings struct
+    pub async fn destroy(mut self) -> (I, ADXL343Settings) {
+        let _ = self.turn_off_measurements().await;
+        (self.i2c, self.settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use std::vec;
+    use core::{future::Future, pin::pin, task::{Context, Poll, Waker}};
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+    use crate::registers::{ADXL343_ADDR, DATA_FORMAT_ADDR};
+    use super::*;
+
+    //the mock completes every operation immediately, so polling once is enough
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let mut context = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn init_and_read(){
+        let mut settings = ADXL343Settings::default();
+        settings.set_range(crate::AccelRange::_4g);
+        let mut expectations: std::vec::Vec<I2cTransaction> = settings.init_register_writes().unwrap().iter()
+            .map(|(address, value)| I2cTransaction::write(ADXL343_ADDR, vec![address, value]))
+            .collect();
+        expectations.extend([
+            I2cTransaction::write(ADXL343_ADDR, vec![POWER_CTL_ADDR, 0x08]),
+            I2cTransaction::write_read(ADXL343_ADDR, vec![DATAX0_ADDR], vec![0x80, 0x00, 0, 0, 0x80, 0xFF]),
+            I2cTransaction::write(ADXL343_ADDR, vec![POWER_CTL_ADDR, 0x00]),
+        ]);
+        let mut sensor = ADXL343InterfaceAsync::new(I2cMock::new(&expectations));
+        sensor.with_settings(settings).unwrap();
+        block_on(sensor.init()).unwrap();
+        block_on(sensor.begin_measurements()).unwrap();
+        assert!(sensor.settings().in_measurement_mode());
+        //10 bit at 4 g: 128 lsb per g
+        assert_eq!(block_on(sensor.read_accel()).unwrap(), [1.0, 0.0, -1.0]);
+
+        let (mut i2c, _) = block_on(sensor.destroy());
+        i2c.done();
+    }
+
+    #[test]
+    fn shared_checks(){
+        let mut sensor = ADXL343InterfaceAsync::new(I2cMock::new(&[]));
+        let mut settings = ADXL343Settings::default();
+        settings.toggle_measurement_mode();
+        assert!(matches!(sensor.with_settings(settings), Err(ADXL343Error::MeasurementModeBeforeConfig)));
+
+        let mut settings = ADXL343Settings::default();
+        settings.set_low_power_mode(true);
+        settings.set_odr(crate::OutputDataRate::Hz800);
+        sensor.with_settings(settings).unwrap();
+        assert!(matches!(block_on(sensor.init()), Err(ADXL343Error::LowPowerOdrUnsupported)));
+
+        let (mut i2c, _) = block_on(sensor.destroy());
+        i2c.done();
+    }
+
+    #[test]
+    fn dump(){
+        let mut values = vec![0u8; DUMP_LEN];
+        values[(DATA_FORMAT_ADDR - THRESH_TAP_ADDR) as usize] = 0x0B;
+        let expectations = [I2cTransaction::write_read(ADXL343_ADDR, vec![THRESH_TAP_ADDR], values)];
+        let mut sensor = ADXL343InterfaceAsync::new(I2cMock::new(&expectations));
+        let dump = block_on(sensor.dump_registers()).unwrap();
+        assert_eq!(dump.get(DATA_FORMAT_ADDR), Some(0x0B));
+
+        let (mut i2c, _) = block_on(sensor.destroy());
+        i2c.done();
+    }
+}
@@ -0,0 +1,393 @@
+use core::fmt::Debug;
+use embedded_hal::{
+    i2c::{I2c, Operation as I2cOperation},
+    spi::{Operation, SpiDevice},
+};
+
+use crate::registers::{ADXL343_ADDR, ADXL343_ALT_ADDR, DATAX0_ADDR};
+
+/// SPI address byte bit selecting a read (1) or a write (0)
+pub const SPI_READ: u8 = 0x80;
+/// SPI address byte bit selecting a multi-byte transfer
+pub const SPI_MULTI_BYTE: u8 = 0x40;
+/// minimum time between the end of a data register read and the next FIFO read, for the entry to pop
+pub const FIFO_POP_DELAY_NS: u32 = 5_000;
+/// bits on the I2c bus to read one register: start, address + write and the register address (9 bits 
+/// each, with the ack), repeated start, address + read, the data byte with its nack, and stop
+pub const I2C_BITS_PER_REGISTER_READ: u32 = 39;
+/// bits on the SPI bus to read one register: the command byte and the data byte
+pub const SPI_BITS_PER_REGISTER_READ: u32 = 16;
+/// FIFO entries read per I2c transaction by I2cBus::read_fifo_entries. Each entry takes two 
+/// operations on the stack (about 12 bytes each on a 32-bit target, 24 on a 64-bit host)
+pub const I2C_FIFO_CHUNK_ENTRIES: usize = 8;
+/// fastest I2c clock the device supports (fast mode), the default of I2cBus
+pub const I2C_MAX_CLOCK_HZ: u32 = 400_000;
+/// fastest SPI clock the device supports, the default of SpiBus
+pub const SPI_MAX_CLOCK_HZ: u32 = 5_000_000;
+
+/// Register level access to the adxl343, implemented for both of its serial interfaces
+pub trait RegisterBus {
+    type Error: Debug;
+
+    /// reads buffer.len() consecutive registers, starting at reg_address
+    fn read_registers(&mut self, reg_address: u8, buffer: &mut [u8]) -> Result<(), Self::Error>;
+
+    fn write_register(&mut self, reg_address: u8, value: u8) -> Result<(), Self::Error>;
+
+    /// reads samples.len() consecutive FIFO entries, each one a DATAX0 - DATAZ1 burst. Reads one entry
+    /// per call of read_registers unless the bus can group them while respecting the FIFO pop delay
+    fn read_fifo_entries(&mut self, samples: &mut [[u8; 6]]) -> Result<(), Self::Error> {
+        for sample in samples {
+            self.read_registers(DATAX0_ADDR, sample)?;
+        }
+        Ok(())
+    }
+
+    /// true when the device must be placed in 3-wire SPI mode (SPI bit of DATA_FORMAT)
+    fn three_wire_spi(&self) -> bool {
+        false
+    }
+
+    /// time a single register read takes on the bus, in ns, used to turn a time limit into a number 
+    /// of polls (see ADXL343Interface::calibrate_offsets). The default is a read at the fastest 
+    /// clock the device supports (5 MHz SPI), so limits are never cut short
+    fn register_read_ns(&self) -> u32 {
+        SPI_BITS_PER_REGISTER_READ * (1_000_000_000 / SPI_MAX_CLOCK_HZ)
+    }
+}
+
+/// I2c device address, selected by the ALT ADDRESS pin (pin 12)
+/// 
+/// # Variants
+/// 
+/// - `#[default] Primary = 0x53` - ALT ADDRESS grounded
+/// - `Alternate = 0x1D` - ALT ADDRESS high
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum I2cAddress {
+    #[default]
+    Primary = ADXL343_ADDR as isize,
+    Alternate = ADXL343_ALT_ADDR as isize,
+}
+
+impl I2cAddress {
+    pub fn address(&self) -> u8 {
+        *self as u8
+    }
+}
+
+/// The adxl343 on an I2c bus
+pub struct I2cBus<I> {
+    i2c: I,
+    address: u8,
+    clock_hz: u32,
+}
+
+impl<I: I2c> I2cBus<I> {
+    /// device at the primary address (0x53)
+    pub fn new(i2c: I) -> Self {
+        Self::new_with_address(i2c, I2cAddress::Primary)
+    }
+
+    pub fn new_with_address(i2c: I, address: I2cAddress) -> Self {
+        Self {
+            i2c,
+            address: address.address(),
+            clock_hz: I2C_MAX_CLOCK_HZ
+        }
+    }
+
+    /// I2c clock rate the bus runs at (I2C_MAX_CLOCK_HZ by default), which bounds the polling of 
+    /// register bits, see RegisterBus::register_read_ns. Setting it too high shortens the limits
+    pub fn with_clock_hz(mut self, clock_hz: u32) -> Self {
+        self.clock_hz = clock_hz.max(1);
+        self
+    }
+
+    pub fn clock_hz(&self) -> u32 {
+        self.clock_hz
+    }
+
+    /// device address used for every transaction
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    /// returns the i2c bus adapter
+    pub fn release(self) -> I {
+        self.i2c
+    }
+}
+
+impl<I: I2c> RegisterBus for I2cBus<I> {
+    type Error = I::Error;
+
+    fn read_registers(&mut self, reg_address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.i2c.write_read(self.address, &[reg_address], buffer)
+    }
+
+    fn write_register(&mut self, reg_address: u8, value: u8) -> Result<(), Self::Error> {
+        self.i2c.write(self.address, &[reg_address, value])
+    }
+
+    /// reads up to I2C_FIFO_CHUNK_ENTRIES entries per transaction, as (write DATAX0, read 6 bytes) pairs.
+    ///
+    /// An entry pops once its last data byte is read. As with SPI at 1.5 MHz or less, where the 
+    /// datasheet counts the 8 bit address byte (5.3 us) as a sufficient delay, the addressing of the 
+    /// next entry delays its first data bit: repeated start (1 bit), address + write (9 bits), DATAX0 
+    /// (9 bits), repeated start (1 bit) and address + read (9 bits), 29 bit times. At 400 kHz, the 
+    /// fastest clock the device supports, that is 72.5 us, well over the 5 us FIFO pop delay
+    fn read_fifo_entries(&mut self, samples: &mut [[u8; 6]]) -> Result<(), Self::Error> {
+        const DATA_ADDRESS: [u8; 1] = [DATAX0_ADDR];
+        for chunk in samples.chunks_mut(I2C_FIFO_CHUNK_ENTRIES) {
+            let count = chunk.len();
+            let mut entries = chunk.iter_mut();
+            let mut operations: [I2cOperation<'_>; 2 * I2C_FIFO_CHUNK_ENTRIES] = core::array::from_fn(|index| {
+                match index % 2 {
+                    0 => I2cOperation::Write(&DATA_ADDRESS),
+                    _ => I2cOperation::Read(entries.next().map_or(&mut [], |entry| entry.as_mut_slice()))
+                }
+            });
+            self.i2c.transaction(self.address, &mut operations[..2 * count])?;
+        }
+        Ok(())
+    }
+
+    fn register_read_ns(&self) -> u32 {
+        (I2C_BITS_PER_REGISTER_READ as u64 * 1_000_000_000 / self.clock_hz as u64) as u32
+    }
+}
+
+/// The adxl343 on an SPI bus (4-wire, or 3-wire with SDI/SDO sharing one line). The SpiDevice
+/// must be configured for SPI mode 3 (CPOL = 1, CPHA = 1) at up to 5 MHz
+pub struct SpiBus<S> {
+    spi: S,
+    three_wire: bool,
+    clock_hz: u32,
+}
+
+impl<S: SpiDevice> SpiBus<S> {
+    /// 4-wire SPI
+    pub fn new(spi: S) -> Self {
+        Self {
+            spi,
+            three_wire: false,
+            clock_hz: SPI_MAX_CLOCK_HZ
+        }
+    }
+
+    /// 3-wire SPI, the SPI bit of DATA_FORMAT is set when the device is initialized
+    pub fn new_3wire(spi: S) -> Self {
+        Self {
+            spi,
+            three_wire: true,
+            clock_hz: SPI_MAX_CLOCK_HZ
+        }
+    }
+
+    /// SPI clock rate the SpiDevice is configured for (SPI_MAX_CLOCK_HZ by default), which bounds 
+    /// the polling of register bits, see RegisterBus::register_read_ns
+    pub fn with_clock_hz(mut self, clock_hz: u32) -> Self {
+        self.clock_hz = clock_hz.max(1);
+        self
+    }
+
+    pub fn clock_hz(&self) -> u32 {
+        self.clock_hz
+    }
+
+    /// returns the spi device
+    pub fn release(self) -> S {
+        self.spi
+    }
+}
+
+impl<S: SpiDevice> RegisterBus for SpiBus<S> {
+    type Error = S::Error;
+
+    fn read_registers(&mut self, reg_address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        let mut command = reg_address | SPI_READ;
+        if buffer.len() > 1 {
+            command |= SPI_MULTI_BYTE;
+        }
+        self.spi.transaction(&mut [Operation::Write(&[command]), Operation::Read(buffer)])
+    }
+
+    fn write_register(&mut self, reg_address: u8, value: u8) -> Result<(), Self::Error> {
+        self.spi.write(&[reg_address, value])
+    }
+
+    /// one transaction per entry, as CS must be deasserted for the entry to pop. Every entry after 
+    /// the first waits FIFO_POP_DELAY_NS before addressing the device, which above 1.5 MHz is 
+    /// not guaranteed by the address byte alone
+    fn read_fifo_entries(&mut self, samples: &mut [[u8; 6]]) -> Result<(), Self::Error> {
+        const COMMAND: [u8; 1] = [DATAX0_ADDR | SPI_READ | SPI_MULTI_BYTE];
+        for (index, sample) in samples.iter_mut().enumerate() {
+            match index {
+                0 => self.spi.transaction(&mut [
+                    Operation::Write(&COMMAND),
+                    Operation::Read(sample)
+                ])?,
+                _ => self.spi.transaction(&mut [
+                    Operation::DelayNs(FIFO_POP_DELAY_NS),
+                    Operation::Write(&COMMAND),
+                    Operation::Read(sample)
+                ])?
+            }
+        }
+        Ok(())
+    }
+
+    fn three_wire_spi(&self) -> bool {
+        self.three_wire
+    }
+
+    fn register_read_ns(&self) -> u32 {
+        (SPI_BITS_PER_REGISTER_READ as u64 * 1_000_000_000 / self.clock_hz as u64) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use std::vec;
+    use embedded_hal_mock::eh1::{
+        i2c::{Mock as I2cMock, Transaction as I2cTransaction},
+        spi::{Mock as SpiMock, Transaction as SpiTransaction},
+    };
+    use super::*;
+
+    #[test]
+    fn spi_address_byte(){
+        let expectations = [
+            SpiTransaction::transaction_start(),
+            SpiTransaction::write_vec(vec![SPI_READ]),
+            SpiTransaction::read_vec(vec![0xE5]),
+            SpiTransaction::transaction_end(),
+            SpiTransaction::transaction_start(),
+            SpiTransaction::write_vec(vec![0x32 | SPI_READ | SPI_MULTI_BYTE]),
+            SpiTransaction::read_vec(vec![1, 2, 3, 4, 5, 6]),
+            SpiTransaction::transaction_end(),
+            SpiTransaction::transaction_start(),
+            SpiTransaction::write_vec(vec![0x2D, 0x08]),
+            SpiTransaction::transaction_end(),
+        ];
+        let mut bus = SpiBus::new(SpiMock::new(&expectations));
+
+        let mut devid = [0u8];
+        bus.read_registers(0x00, &mut devid).unwrap();
+        assert_eq!(devid, [0xE5]);
+        let mut sample = [0u8; 6];
+        bus.read_registers(0x32, &mut sample).unwrap();
+        assert_eq!(sample, [1, 2, 3, 4, 5, 6]);
+        bus.write_register(0x2D, 0x08).unwrap();
+
+        bus.release().done();
+    }
+
+    #[test]
+    fn i2c_transactions(){
+        let expectations = [
+            I2cTransaction::write_read(ADXL343_ADDR, vec![0x00], vec![0xE5]),
+            I2cTransaction::write(ADXL343_ADDR, vec![0x2D, 0x08]),
+        ];
+        let mut bus = I2cBus::new(I2cMock::new(&expectations));
+
+        let mut devid = [0u8];
+        bus.read_registers(0x00, &mut devid).unwrap();
+        assert_eq!(devid, [0xE5]);
+        bus.write_register(0x2D, 0x08).unwrap();
+
+        bus.release().done();
+    }
+
+    #[test]
+    fn i2c_fifo_burst(){
+        let expectations = [
+            I2cTransaction::transaction_start(ADXL343_ADDR),
+            I2cTransaction::write(ADXL343_ADDR, vec![DATAX0_ADDR]),
+            I2cTransaction::read(ADXL343_ADDR, vec![1, 2, 3, 4, 5, 6]),
+            I2cTransaction::write(ADXL343_ADDR, vec![DATAX0_ADDR]),
+            I2cTransaction::read(ADXL343_ADDR, vec![7, 8, 9, 10, 11, 12]),
+            I2cTransaction::transaction_end(ADXL343_ADDR),
+        ];
+        let mut bus = I2cBus::new(I2cMock::new(&expectations));
+
+        let mut samples = [[0u8; 6]; 2];
+        bus.read_fifo_entries(&mut samples).unwrap();
+        assert_eq!(samples, [[1, 2, 3, 4, 5, 6], [7, 8, 9, 10, 11, 12]]);
+
+        bus.release().done();
+    }
+
+    #[test]
+    fn i2c_fifo_chunks(){
+        let mut expectations = vec![];
+        for chunk in [0..I2C_FIFO_CHUNK_ENTRIES, I2C_FIFO_CHUNK_ENTRIES..I2C_FIFO_CHUNK_ENTRIES + 2] {
+            expectations.push(I2cTransaction::transaction_start(ADXL343_ADDR));
+            for entry in chunk {
+                expectations.push(I2cTransaction::write(ADXL343_ADDR, vec![DATAX0_ADDR]));
+                expectations.push(I2cTransaction::read(ADXL343_ADDR, vec![entry as u8; 6]));
+            }
+            expectations.push(I2cTransaction::transaction_end(ADXL343_ADDR));
+        }
+        let mut bus = I2cBus::new(I2cMock::new(&expectations));
+
+        let mut samples = [[0u8; 6]; I2C_FIFO_CHUNK_ENTRIES + 2];
+        bus.read_fifo_entries(&mut samples).unwrap();
+        assert!(samples.iter().enumerate().all(|(entry, sample)| *sample == [entry as u8; 6]));
+
+        bus.release().done();
+    }
+
+    #[test]
+    fn spi_fifo_entries(){
+        let command = DATAX0_ADDR | SPI_READ | SPI_MULTI_BYTE;
+        let expectations = [
+            SpiTransaction::transaction_start(),
+            SpiTransaction::write_vec(vec![command]),
+            SpiTransaction::read_vec(vec![1, 2, 3, 4, 5, 6]),
+            SpiTransaction::transaction_end(),
+            SpiTransaction::transaction_start(),
+            SpiTransaction::delay(FIFO_POP_DELAY_NS),
+            SpiTransaction::write_vec(vec![command]),
+            SpiTransaction::read_vec(vec![7, 8, 9, 10, 11, 12]),
+            SpiTransaction::transaction_end(),
+        ];
+        let mut bus = SpiBus::new(SpiMock::new(&expectations));
+
+        let mut samples = [[0u8; 6]; 2];
+        bus.read_fifo_entries(&mut samples).unwrap();
+        assert_eq!(samples, [[1, 2, 3, 4, 5, 6], [7, 8, 9, 10, 11, 12]]);
+
+        bus.release().done();
+    }
+
+    #[test]
+    fn i2c_alternate_address(){
+        let expectations = [
+            I2cTransaction::write_read(0x1D, vec![0x00], vec![0xE5]),
+        ];
+        let mut bus = I2cBus::new_with_address(I2cMock::new(&expectations), I2cAddress::Alternate);
+        assert_eq!(bus.address(), 0x1D);
+
+        let mut devid = [0u8];
+        bus.read_registers(0x00, &mut devid).unwrap();
+
+        bus.release().done();
+    }
+
+    #[test]
+    fn register_read_time(){
+        let i2c = I2cBus::new(I2cMock::new(&[]));
+        assert_eq!(i2c.register_read_ns(), 97_500);
+        let i2c = i2c.with_clock_hz(100_000);
+        assert_eq!(i2c.register_read_ns(), 390_000);
+        i2c.release().done();
+
+        let spi = SpiBus::new(SpiMock::new(&[]));
+        assert_eq!(spi.register_read_ns(), 3_200);
+        let spi = spi.with_clock_hz(1_000_000);
+        assert_eq!(spi.register_read_ns(), 16_000);
+        spi.release().done();
+    }
+}
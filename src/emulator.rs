@@ -0,0 +1,337 @@
+//! Software model of an ADXL343 on an I2c bus, for testing the driver on a host without hardware.
+//!
+//! [`EmulatedADXL343`] implements `embedded_hal::i2c::I2c`, so it can be handed to
+//! [`ADXL343Interface::new`](crate::ADXL343Interface::new) in place of a real bus. It models:
+//! - the register file, with DEVID and the reset values, ignoring writes to read-only registers
+//! - auto-incrementing multi-byte reads and writes (the first byte of a write selects the register)
+//! - a programmable acceleration vector, with the OFSX - OFSZ offsets and the self-test force applied,
+//!   encoded in DATAX0 - DATAZ1 according to DATA_FORMAT (range, resolution and justification)
+//! - the FIFO in FIFO and stream mode: [`EmulatedADXL343::tick`] takes samples at the ODR, and 
+//!   every read touching the data registers pops one entry. Trigger events are not modelled, so 
+//!   trigger mode behaves like stream mode
+//! - DATA_READY, WATERMARK and OVERRUN in INT_SOURCE, plus injectable tap/activity/free fall events
+//!
+//! Time does not pass on its own: in bypass mode a new sample is always ready while measuring,
+//! in the other modes samples only arrive through tick.
+use embedded_hal::i2c::{ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation, SevenBitAddress};
+
+use crate::{
+    bus::I2cAddress,
+    registers::{
+        DEVID_ADDR, DEVID_REG_VALUE, THRESH_TAP_ADDR, TAP_AXES_ADDR, BW_RATE_ADDR, INT_MAP_ADDR, INT_SOURCE_ADDR,
+        DATA_FORMAT_ADDR, DATAX0_ADDR, DATAZ1_ADDR, FIFO_CTL_ADDR, FIFO_STATUS_ADDR, POWER_CTL_ADDR,
+        OFSX_ADDR, INT_ENABLE_ADDR,
+        accel_configs::{DATA_FORMAT, FIFO_CTL, FIFO_STATUS, POWER_CTL, FIFOMode, AccelRange, FullRes, Alignment}
+    },
+    utils::{interrupts::InterruptSet, offsets::OFS_G_PER_LSB, self_test::SELF_TEST_LIMITS_G},
+};
+
+/// number of addressable registers (0x00 - 0x39)
+const REGISTER_COUNT: usize = FIFO_STATUS_ADDR as usize + 1;
+
+/// number of samples the FIFO holds
+pub const FIFO_DEPTH: usize = 32;
+
+/// output change [x_axis, y_axis, z_axis] in g's while the self-test force is applied (the middle of
+/// the datasheet limits at a 2.5 V supply)
+pub const EMULATED_SELF_TEST_G: [f32; 3] = [
+    (SELF_TEST_LIMITS_G[0].0 + SELF_TEST_LIMITS_G[0].1) / 2.0,
+    (SELF_TEST_LIMITS_G[1].0 + SELF_TEST_LIMITS_G[1].1) / 2.0,
+    (SELF_TEST_LIMITS_G[2].0 + SELF_TEST_LIMITS_G[2].1) / 2.0,
+];
+
+/// Simulated ADXL343 implementing `embedded_hal::i2c::I2c`, see the module documentation
+#[derive(Clone, Debug)]
+pub struct EmulatedADXL343 {
+    address: u8,
+    registers: [u8; REGISTER_COUNT],
+    pointer: u8,
+    accel_g: [f32; 3],
+    fifo: [[f32; 3]; FIFO_DEPTH],
+    fifo_len: usize,
+    overrun: bool,
+    events: InterruptSet,
+}
+
+impl Default for EmulatedADXL343 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EmulatedADXL343 {
+    /// Device at the primary address 0x53, with the registers at their reset values,
+    /// lying flat (reading [0, 0, 1] g)
+    pub fn new() -> Self {
+        Self::new_with_address(I2cAddress::Primary)
+    }
+
+    /// Same as new, responding to the given address
+    pub fn new_with_address(address: I2cAddress) -> Self {
+        let mut registers = [0u8; REGISTER_COUNT];
+        registers[DEVID_ADDR as usize] = DEVID_REG_VALUE;
+        registers[BW_RATE_ADDR as usize] = 0x0A;
+        EmulatedADXL343 {
+            address: address.address(),
+            registers,
+            pointer: 0,
+            accel_g: [0.0, 0.0, 1.0],
+            fifo: [[0.0; 3]; FIFO_DEPTH],
+            fifo_len: 0,
+            overrun: false,
+            events: InterruptSet::empty(),
+        }
+    }
+
+    /// Sets the acceleration [x_axis, y_axis, z_axis] in g's the device measures from now on
+    pub fn set_acceleration(&mut self, accel_g: [f32; 3]) {
+        self.accel_g = accel_g;
+    }
+
+    pub fn acceleration(&self) -> [f32; 3] {
+        self.accel_g
+    }
+
+    /// Value of the register at address, without the side effects of a bus read
+    pub fn register(&self, address: u8) -> u8 {
+        match address {
+            INT_SOURCE_ADDR => self.int_source().bits(),
+            FIFO_STATUS_ADDR => FIFO_STATUS::new().with_entries(self.fifo_len as u8).into_bytes()[0],
+            DATAX0_ADDR..=DATAZ1_ADDR => self.encode_sample(self.current_sample())[(address - DATAX0_ADDR) as usize],
+            _ => self.registers.get(address as usize).copied().unwrap_or(0)
+        }
+    }
+
+    /// number of samples stored in the FIFO
+    pub fn fifo_entries(&self) -> usize {
+        self.fifo_len
+    }
+
+    /// Simulates `samples` sample periods: while measuring with the FIFO enabled, each one stores the
+    /// current acceleration in the FIFO. A full FIFO stops collecting in FIFO mode, and drops its
+    /// oldest sample in stream mode (and in trigger mode, which is not told apart), setting OVERRUN
+    pub fn tick(&mut self, samples: usize) {
+        if !self.measuring() {
+            return;
+        }
+        let mode = self.fifo_ctl().fifo_mode();
+        for _ in 0..samples {
+            let sample = self.measured_accel();
+            match (mode, self.fifo_len == FIFO_DEPTH) {
+                (FIFOMode::BYPASS, _) => return,
+                (_, false) => {
+                    self.fifo[self.fifo_len] = sample;
+                    self.fifo_len += 1;
+                },
+                (FIFOMode::FIFO, true) => return,
+                (_, true) => {
+                    self.fifo.copy_within(1.., 0);
+                    self.fifo[FIFO_DEPTH - 1] = sample;
+                    self.overrun = true;
+                }
+            }
+        }
+    }
+
+    /// Raises events of the functions the emulator does not detect itself (SINGLE_TAP, DOUBLE_TAP,
+    /// ACTIVITY, INACTIVITY and FREE_FALL). They appear in INT_SOURCE when enabled in INT_ENABLE,
+    /// and are cleared by reading INT_SOURCE
+    pub fn trigger_events(&mut self, events: InterruptSet) {
+        self.events.insert(events);
+    }
+
+    fn measuring(&self) -> bool {
+        POWER_CTL::from_bytes([self.registers[POWER_CTL_ADDR as usize]]).measure() != 0
+    }
+
+    fn fifo_ctl(&self) -> FIFO_CTL {
+        FIFO_CTL::from_bytes([self.registers[FIFO_CTL_ADDR as usize]])
+    }
+
+    fn data_format(&self) -> DATA_FORMAT {
+        DATA_FORMAT::from_bytes([self.registers[DATA_FORMAT_ADDR as usize]])
+    }
+
+    fn fifo_enabled(&self) -> bool {
+        self.fifo_ctl().fifo_mode() != FIFOMode::BYPASS
+    }
+
+    fn int_source(&self) -> InterruptSet {
+        let mut source = self.events & InterruptSet::from_bits(self.registers[INT_ENABLE_ADDR as usize]);
+        let data_ready = match self.fifo_enabled() {
+            true => self.fifo_len > 0,
+            false => self.measuring()
+        };
+        source.set(InterruptSet::DATA_READY, data_ready);
+        source.set(InterruptSet::WATERMARK, self.fifo_len >= self.fifo_ctl().samples() as usize);
+        source.set(InterruptSet::OVERRUN, self.overrun);
+        source
+    }
+
+    /// acceleration the device outputs, including the offsets and the self-test force
+    fn measured_accel(&self) -> [f32; 3] {
+        let self_test = self.data_format().self_test() != 0;
+        core::array::from_fn(|axis| {
+            let offset = self.registers[OFSX_ADDR as usize + axis] as i8 as f32 * OFS_G_PER_LSB;
+            let force = if self_test { EMULATED_SELF_TEST_G[axis] } else { 0.0 };
+            self.accel_g[axis] + offset + force
+        })
+    }
+
+    /// sample presented in DATAX0 - DATAZ1: the oldest FIFO entry, or the live reading in bypass mode
+    fn current_sample(&self) -> [f32; 3] {
+        match self.fifo_enabled() && self.fifo_len > 0 {
+            true => self.fifo[0],
+            false => self.measured_accel()
+        }
+    }
+
+    /// encodes a sample as [x_low, x_high, y_low, y_high, z_low, z_high] according to DATA_FORMAT
+    fn encode_sample(&self, accel_g: [f32; 3]) -> [u8; 6] {
+        let data_format = self.data_format();
+        let range_g: f32 = match data_format.range() {
+            AccelRange::_2g => 2.0,
+            AccelRange::_4g => 4.0,
+            AccelRange::_8g => 8.0,
+            AccelRange::_16g => 16.0
+        };
+        let (bits, lsb_per_g) = match data_format.full_res() {
+            FullRes::full_res => (10 + (range_g as u32).trailing_zeros() - 1, 256.0),
+            FullRes::_10bit_res => (10, 512.0 / range_g)
+        };
+        let max = (1i32 << (bits - 1)) - 1;
+        let min = -(1i32 << (bits - 1));
+
+        let mut sample = [0u8; 6];
+        for (axis, g) in accel_g.iter().enumerate() {
+            let lsbs = g * lsb_per_g;
+            let lsbs = if lsbs < 0.0 { lsbs - 0.5 } else { lsbs + 0.5 } as i32;
+            let value = lsbs.clamp(min, max) as i16;
+            let encoded = match data_format.justisfy() {
+                Alignment::right => value,
+                Alignment::left => value << (16 - bits)
+            };
+            sample[2 * axis..2 * axis + 2].copy_from_slice(&encoded.to_le_bytes());
+        }
+        sample
+    }
+
+    fn writable(address: u8) -> bool {
+        matches!(address, THRESH_TAP_ADDR..=TAP_AXES_ADDR | BW_RATE_ADDR..=INT_MAP_ADDR | DATA_FORMAT_ADDR | FIFO_CTL_ADDR)
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        let Some((&pointer, values)) = bytes.split_first() else {
+            return;
+        };
+        self.pointer = pointer;
+        for &value in values {
+            if Self::writable(self.pointer) {
+                self.registers[self.pointer as usize] = value;
+            }
+            if self.pointer == FIFO_CTL_ADDR && !self.fifo_enabled() {
+                //bypass mode clears the FIFO
+                self.fifo_len = 0;
+                self.overrun = false;
+            }
+            self.pointer = self.pointer.wrapping_add(1);
+        }
+    }
+
+    fn read_bytes(&mut self, buffer: &mut [u8]) {
+        //the data registers are latched for the whole read, and the FIFO pops once afterwards
+        let sample = self.encode_sample(self.current_sample());
+        let mut read_data = false;
+        let mut read_int_source = false;
+        for byte in buffer.iter_mut() {
+            *byte = match self.pointer {
+                DATAX0_ADDR..=DATAZ1_ADDR => {
+                    read_data = true;
+                    sample[(self.pointer - DATAX0_ADDR) as usize]
+                },
+                INT_SOURCE_ADDR => {
+                    read_int_source = true;
+                    self.register(INT_SOURCE_ADDR)
+                },
+                address => self.register(address)
+            };
+            self.pointer = self.pointer.wrapping_add(1);
+        }
+        if read_int_source {
+            self.events = InterruptSet::empty();
+        }
+        if read_data && self.fifo_enabled() && self.fifo_len > 0 {
+            self.fifo.copy_within(1.., 0);
+            self.fifo_len -= 1;
+            self.overrun = false;
+        }
+    }
+}
+
+impl ErrorType for EmulatedADXL343 {
+    type Error = ErrorKind;
+}
+
+impl I2c<SevenBitAddress> for EmulatedADXL343 {
+    fn transaction(&mut self, address: SevenBitAddress, operations: &mut [Operation<'_>]) -> Result<(), Self::Error> {
+        if address != self.address {
+            return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+        }
+        for operation in operations {
+            match operation {
+                Operation::Write(bytes) => self.write_bytes(bytes),
+                Operation::Read(buffer) => self.read_bytes(buffer)
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_encoding(){
+        let mut device = EmulatedADXL343::new();
+        device.set_acceleration([1.0, -1.0, 0.5]);
+        //10-bit, +/-2g, right justified
+        assert_eq!(device.encode_sample(device.acceleration()), [0x00, 0x01, 0x00, 0xFF, 0x80, 0x00]);
+
+        //full resolution, +/-16g, left justified (13 bits)
+        device.registers[DATA_FORMAT_ADDR as usize] = DATA_FORMAT::new()
+            .with_range(AccelRange::_16g)
+            .with_full_res(FullRes::full_res)
+            .with_justisfy(Alignment::left).into_bytes()[0];
+        assert_eq!(device.encode_sample([1.0, -1.0, 20.0]), [0x00, 0x08, 0x00, 0xF8, 0xF8, 0x7F]);
+    }
+
+    #[test]
+    fn fifo_modes(){
+        let mut device = EmulatedADXL343::new();
+        device.write_bytes(&[FIFO_CTL_ADDR, FIFO_CTL::new().with_fifo_mode(FIFOMode::FIFO).with_samples(4).into_bytes()[0]]);
+        device.tick(3);
+        assert_eq!(device.fifo_entries(), 0); //standby
+        device.write_bytes(&[POWER_CTL_ADDR, 0x08]);
+        device.tick(3);
+        assert_eq!(device.fifo_entries(), 3);
+        assert!(!device.int_source().contains(InterruptSet::WATERMARK));
+        device.tick(FIFO_DEPTH);
+        assert_eq!(device.fifo_entries(), FIFO_DEPTH);
+        assert!(device.int_source().contains(InterruptSet::WATERMARK));
+        assert!(!device.int_source().contains(InterruptSet::OVERRUN));
+
+        device.write_bytes(&[FIFO_CTL_ADDR, FIFO_CTL::new().with_fifo_mode(FIFOMode::STREAM).into_bytes()[0]]);
+        device.tick(1);
+        assert_eq!(device.fifo_entries(), FIFO_DEPTH);
+        assert!(device.int_source().contains(InterruptSet::OVERRUN));
+
+        let mut sample = [0u8; 6];
+        device.write_bytes(&[DATAX0_ADDR]);
+        device.read_bytes(&mut sample);
+        assert_eq!(device.fifo_entries(), FIFO_DEPTH - 1);
+
+        device.write_bytes(&[FIFO_CTL_ADDR, 0x00]);
+        assert_eq!(device.fifo_entries(), 0);
+    }
+}
@@ -6,11 +6,37 @@
 //! [`embedded-hal`]: https://github.com/rust-embedded/embedded-hal
 //!
 //! This driver allows you to configure:
-//! - ODR ([`OutputDataRate`])
+//! - ODR ([`OutputDataRate`]), and its rate in Hz, sample period, bandwidth and supply current, or the 
+//!   ODR nearest to a rate in Hz
 //! - Justification (i.e. left or right alignment of data) ([`Alignment`])
 //! - Resolution (i.e. whether to use 10 bits or full number of bits to represent measurement) 
-//! ([`FullRes`])
+//!   ([`FullRes`])
 //! - Range of measurements ([`AccelRange`])
+//! - FIFO buffer mode, watermark and trigger line ([`FIFOMode`], [`InterruptPin`])
+//! - Single and double tap detection ([`TapConfig`])
+//! - Activity and inactivity detection ([`ActivityConfig`])
+//! - Free fall detection ([`FreeFallConfig`])
+//! - Interrupt enables and routing to the INT1/INT2 pins ([`InterruptSet`]), and reading every 
+//!   pending event at once ([`Events`])
+//! - Per-axis offsets, and their automatic calibration ([`CalibrationOrientation`])
+//! - Built-in self-test with datasheet pass/fail limits ([`SelfTestReport`])
+//! - Sleep, auto-sleep, link mode and the sleep mode wakeup rate ([`SLEEP_MODE_ODR`])
+//! - Low power mode, with the expected supply current for the chosen ODR
+//! - A shadow of every written register, with optional verify-after-write and read-back 
+//!   verification of the whole configuration ([`RegisterShadow`])
+//! - Reading the configuration back from the device into [`ADXL343Settings`]
+//! - Register dumps printed as a decoded register map, and diffs between dumps ([`RegisterDump`]), 
+//!   with `defmt::Format` implementations behind the `defmt` feature
+//! - Acceleration in integer milli-g's, or as fixed point numbers behind the `fixed` feature, for 
+//!   targets without an FPU
+//! - A software model of the device implementing `embedded_hal::i2c::I2c` (`EmulatedADXL343`, 
+//!   behind the `emulator` feature), for testing without hardware
+//! - Pitch, roll and tilt angles from the gravity vector ([`Tilt`]), and a six-face orientation 
+//!   classifier with hysteresis ([`FaceClassifier`])
+//! - Chainable low-pass, high-pass and moving average filters for readings, reset when the ODR 
+//!   changes ([`Filter`])
+//! - Validation of a configuration against the I2c bus speed and the datasheet limitations, reporting 
+//!   errors and warnings ([`ValidationReport`]), optionally enforced by `init_validated`
 //!
 //! ## The Device
 //! 
@@ -19,9 +45,13 @@
 //! with 1/256 resolution. 
 //! 
 //! This particular driver is capable of interfacing with the ADXL343 device over an a bus 
-//! implemeing the I2c communication protocol.To ensure proper functionality, ground the alternate address pin: 
-//! Pin 12. When the alternate address pin is high, the device address is 0x1D, instead of the intended 
-//! 0x53. For more information visit the link below, to the datasheet.
+//! implemeing the I2c communication protocol, or over 3-wire or 4-wire SPI (through an 
+//! `embedded_hal::spi::SpiDevice`, see [`ADXL343Interface::new_spi`] and 
+//! [`ADXL343Interface::new_spi_3wire`]). When using I2c, the device address is selected by the 
+//! alternate address pin (Pin 12): 0x53 when grounded ([`I2cAddress::Primary`], used by 
+//! [`ADXL343Interface::new`]) and 0x1D when high ([`I2cAddress::Alternate`], see 
+//! [`ADXL343Interface::new_with_address`]), so two devices can share one bus. For more information 
+//! visit the link below, to the datasheet.
 
 //!
 //! Datasheet:
@@ -65,6 +95,39 @@
 //! let raw_accel_data: [u8; 6] = sensor.read_full_sample()?; //use a match to handle errors
 //! let accel_data_g: [f32; 3] = sensor.read_accel()?; //obtain accelerometer reading in g's
 //! ```
+//! ### Typestate Driver
+//! [`ADXL343`] wraps the interface and tracks its state in the type: `init` turns an [`Unconfigured`]
+//! handle into a [`Configured`] one, and `begin_measurements` returns a [`Measuring`] handle, the only
+//! state with sample reading methods.
+//!
+//! ```
+//! let sensor = ADXL343::new(i2c).with_settings(settings)?.init()?;
+//! let mut sensor = sensor.begin_measurements()?;
+//! let accel_data_g: [f32; 3] = sensor.read_accel()?;
+//! ```
+//!
+//! ### Async
+//! With the `async` feature enabled, [`ADXL343InterfaceAsync`] offers the same init, confirm_device,
+//! begin_measurements, read_full_sample, read_accel and register access as `async fn`s over an
+//! `embedded_hal_async::i2c::I2c` bus (e.g. on Embassy).
+//!
+//! ```
+//! let mut sensor = ADXL343InterfaceAsync::new(i2c);
+//! sensor.with_settings(settings)?;
+//! sensor.init().await?;
+//! sensor.begin_measurements().await?;
+//! let accel_data_g: [f32; 3] = sensor.read_accel().await?;
+//! ```
+//!
+//! ### Wait on the Interrupt Pin
+//! With INT1 wired to a GPIO implementing `embedded_hal::digital::InputPin`, samples can be read
+//! as soon as they are ready instead of polling the device. With the `async` feature, pins 
+//! implementing `embedded_hal_async::digital::Wait` can be awaited instead.
+//!
+//! ```
+//! let mut sensor = ADXL343PinInterface::new(sensor, int1_gpio, InterruptPin::INT1);
+//! let accel_data_g: [f32; 3] = sensor.wait_for_data_ready()?;
+//! ```
 //! ### Destroy and Change Settings
 //! ```
 //! //turns off measurement mode
@@ -171,11 +234,37 @@
 
 pub mod registers; 
 pub mod utils;
+pub mod bus;
 pub mod adxl343_interface;
+pub mod adxl343_int_pin;
+pub mod typestate;
+#[cfg(feature = "emulator")]
+pub mod emulator;
+#[cfg(feature = "async")]
+pub mod adxl343_interface_async;
 
 pub use adxl343_interface::*;
+pub use bus::{RegisterBus, I2cBus, I2cAddress, SpiBus};
+pub use adxl343_int_pin::*;
+pub use typestate::{ADXL343, Unconfigured, Configured, Measuring};
+#[cfg(feature = "emulator")]
+pub use emulator::EmulatedADXL343;
+#[cfg(feature = "async")]
+pub use adxl343_interface_async::*;
 pub use utils::settings::ADXL343Settings;
-pub use registers::accel_configs::{AccelRange, OutputDataRate, Alignment, FullRes};
+pub use utils::axes::{Axis, AxisSet};
+pub use utils::tap::{TapConfig, TapEvent, TapKind};
+pub use utils::activity::{ActivityConfig, ActivityStatus};
+pub use utils::free_fall::FreeFallConfig;
+pub use utils::interrupts::{InterruptSet, Events};
+pub use utils::offsets::CalibrationOrientation;
+pub use utils::self_test::SelfTestReport;
+pub use utils::orientation::{Tilt, Face, FaceClassifier};
+pub use utils::filters::{Filter, Chain, LowPass, HighPass, MovingAverage};
+pub use utils::validation::{ValidationReport, ConfigIssue, Severity};
+pub use registers::shadow::RegisterShadow;
+pub use registers::dump::{RegisterDump, RegisterChange};
+pub use registers::accel_configs::{AccelRange, OutputDataRate, Alignment, FullRes, FIFOMode, InterruptPin, Coupling, SLEEP_MODE_ODR};
 
 
 
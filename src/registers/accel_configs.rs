@@ -1,5 +1,9 @@
-use modular_bitfield::{bitfield, prelude::{B1, B2, B3, B4, B5}, Specifier};
-use super::{BW_RATE_ADDR, FIFO_CTL_ADDR, DATA_FORMAT_ADDR, REGISTER_SIZE};
+use core::time::Duration;
+use modular_bitfield::{bitfield, prelude::{B1, B2, B3, B4, B5, B6}, Specifier};
+use super::{
+    BW_RATE_ADDR, POWER_CTL_ADDR, FIFO_CTL_ADDR, FIFO_STATUS_ADDR, DATA_FORMAT_ADDR, TAP_AXES_ADDR, ACT_TAP_STATUS_ADDR,
+    INT_ENABLE_ADDR, INT_MAP_ADDR, INT_SOURCE_ADDR, ACT_INACT_CTL_ADDR, REGISTER_SIZE
+};
 
 /// Bit field for the BW_RATE register. Configures both the ODR and power consumption settings.
 /// 
@@ -48,7 +52,7 @@ impl Default for BW_RATE {
 /// - `Hz0_39 = 0b0010` 
 /// - `Hz0_20 = 0b0001` 
 /// - `Hz0_10 = 0b0000` 
-#[derive(Default, Specifier, Clone, Copy)]
+#[derive(Default, Specifier, Clone, Copy, PartialEq, Eq, Debug)]
 pub enum OutputDataRate {
     Hz3200 = 0b1111,
     Hz1600 = 0b1110,
@@ -69,37 +73,131 @@ pub enum OutputDataRate {
     Hz0_10 = 0b0000,
 }
 
-/// Configure whether the device will start measuring or not.
+impl OutputDataRate {
+    /// every rate, from the fastest to the slowest
+    pub const ALL: [OutputDataRate; 16] = {
+        use OutputDataRate::*;
+        [
+            Hz3200, Hz1600, Hz800, Hz400, Hz200, Hz100, Hz50, Hz25,
+            Hz12_5, Hz6_25, Hz3_13, Hz1_56, Hz0_78, Hz0_39, Hz0_20, Hz0_10
+        ]
+    };
+
+    /// number of times the rate is halved from 3200 Hz
+    fn halvings(&self) -> u32 {
+        (OutputDataRate::Hz3200 as u8 - *self as u8) as u32
+    }
+
+    /// sample rate in Hz, each step down from 3200 Hz halves the rate (Hz3_13 is 3.125 Hz)
+    pub fn hz(&self) -> f32 {
+        3200.0 / (1u32 << self.halvings()) as f32
+    }
+
+    /// time between samples (312.5 µs at 3200 Hz, 10.24 s at Hz0_10)
+    pub fn period(&self) -> Duration {
+        Duration::from_nanos(312_500 << self.halvings())
+    }
+
+    /// output bandwidth in Hz, half of the ODR
+    pub fn bandwidth_hz(&self) -> f32 {
+        self.hz() / 2.0
+    }
+
+    /// true from 12.5 Hz to 400 Hz, the rates low power mode reduces the supply current for
+    /// (without the increased noise listed above 400 Hz)
+    pub fn supports_low_power(&self) -> bool {
+        use OutputDataRate::*;
+        matches!(self, Hz400 | Hz200 | Hz100 | Hz50 | Hz25 | Hz12_5)
+    }
+
+    /// typical supply current in µA (VS = 2.5 V) in normal power mode, from the datasheet current
+    /// consumption table
+    pub fn normal_current_ua(&self) -> u16 {
+        use OutputDataRate::*;
+        match self {
+            Hz3200 | Hz800 | Hz400 | Hz200 | Hz100 => 140,
+            Hz1600 | Hz50 => 90,
+            Hz25 => 60,
+            Hz12_5 => 50,
+            Hz6_25 => 45,
+            Hz3_13 => 40,
+            Hz1_56 => 34,
+            Hz0_78 | Hz0_39 | Hz0_20 | Hz0_10 => 23
+        }
+    }
+
+    /// typical supply current in µA (VS = 2.5 V) in low power mode, None for rates without
+    /// low power support (see supports_low_power)
+    pub fn low_power_current_ua(&self) -> Option<u16> {
+        use OutputDataRate::*;
+        match self {
+            Hz400 => Some(90),
+            Hz200 => Some(60),
+            Hz100 => Some(50),
+            Hz50 => Some(45),
+            Hz25 => Some(40),
+            Hz12_5 => Some(34),
+            _ => None
+        }
+    }
+
+    /// rate closest to hz (by ratio, so 0.15 Hz is closer to 0.20 Hz than to 0.10 Hz). Rates at
+    /// or below 0 Hz give Hz0_10
+    pub fn nearest(hz: f32) -> Self {
+        if hz.is_nan() || hz <= 0.0 {
+            return OutputDataRate::Hz0_10;
+        }
+        let ratio = |odr: &OutputDataRate| match hz > odr.hz() {
+            true => hz / odr.hz(),
+            false => odr.hz() / hz
+        };
+        let mut nearest = OutputDataRate::Hz3200;
+        for odr in OutputDataRate::ALL {
+            if ratio(&odr) < ratio(&nearest) {
+                nearest = odr;
+            }
+        }
+        nearest
+    }
+
+    /// rate matching hz to within 3%, so both the exact rates (3.125) and the rounded values in the
+    /// datasheet (3.13, 0.10) are accepted. None when no rate matches
+    pub fn from_hz(hz: f32) -> Option<Self> {
+        let nearest = OutputDataRate::nearest(hz);
+        match (hz - nearest.hz()).abs() <= nearest.hz() * 0.03 {
+            true => Some(nearest),
+            false => None
+        }
+    }
+}
+
+/// Configure whether the device will start measuring or not, and its sleep features.
 /// 
 /// # Fields
 /// 
-/// - `#[skip] wakeup` (`SLEEP_MODE_ODR`)  
-/// - `#[skip] sleep` (`B1`)  
-/// - `measure` (`B1`) 
-/// - `#[skip] autosleep` (`B1`) 
-/// - `#[skip] link` (`B1`) 
+/// - `wakeup` (`SLEEP_MODE_ODR`) - frequency of readings while asleep 
+/// - `sleep` (`B1`) - 1 places the device into sleep mode
+/// - `measure` (`B1`) - 1 places the device into measurement mode, 0 into standby
+/// - `autosleep` (`B1`) - 1 (with link set) switches to sleep mode when inactivity is detected
+/// - `link` (`B1`) - 1 serially links the activity and inactivity functions
 /// - `#[skip] __` (`B2`) 
-
+#[derive(Clone, Copy)]
 #[bitfield(bits = 8)]
 pub struct POWER_CTL{
-    #[skip]
-    wakeup: SLEEP_MODE_ODR,
-    #[skip]
-    sleep: B1,
+    pub wakeup: SLEEP_MODE_ODR,
+    pub sleep: B1,
 
     pub measure: B1, 
 
-    #[skip]
-    autosleep: B1,
-    #[skip]
-    link: B1,
+    pub autosleep: B1,
+    pub link: B1,
     #[skip]
     __: B2, // reserved/unused bits
 }
 
 impl POWER_CTL  {
     pub fn address(&self) -> u8 {
-        BW_RATE_ADDR
+        POWER_CTL_ADDR
     }
 }
 
@@ -109,7 +207,15 @@ impl Default for POWER_CTL {
     }
 }
 
-#[derive(Default, Specifier)]
+/// Frequency of readings in sleep mode
+/// 
+/// # Variants
+/// 
+/// - `#[default] _8Hz = 0b00`
+/// - `_4Hz = 0b01`
+/// - `_2Hz = 0b10`
+/// - `_1Hz = 0b11`
+#[derive(Default, Specifier, Clone, Copy, PartialEq, Eq, Debug)]
 pub enum SLEEP_MODE_ODR{
     #[default]
     _8Hz = 0b00,
@@ -122,19 +228,17 @@ pub enum SLEEP_MODE_ODR{
 /// 
 /// # Fields
 /// 
-/// - `#[skip] samples` (`B5`) - must remain zero
-/// - `#[skip] samples` (`B1`) - controls the mapping of the trigger event to the interrupt line;
+/// - `samples` (`B5`) - watermark level in FIFO mode, samples retained before the trigger event
+/// in trigger mode (0 - 31)
+/// - `trigger` (`InterruptPin`) - controls the mapping of the trigger event to the interrupt line;
 /// 0 -> int line 1, 1 -> int line 2
 /// - `fifo_mode` (`FIFO_MODE`) - default, FIFO, STREAM, Trigger
-
+#[derive(Clone, Copy)]
 #[bitfield(bits = 8)]
 pub struct FIFO_CTL{
-    #[skip]
-    samples: B5,
-    #[skip]
-    samples: B1,
-
-    fifo_mode: FIFOMode
+    pub samples: B5,
+    pub trigger: InterruptPin,
+    pub fifo_mode: FIFOMode
 }
 
 impl FIFO_CTL  {
@@ -155,13 +259,291 @@ impl Default for FIFO_CTL {
 /// - `#[default] BYPASS = 0b00` 
 /// - `FIFO = 0b01` 
 /// - `STREAM = 0b10`
-#[derive(Default, Specifier)]
+/// - `TRIGGER = 0b11`
+#[derive(Default, Specifier, Clone, Copy, PartialEq, Eq, Debug)]
 #[bits = 2]
 pub enum FIFOMode{
     #[default]
     BYPASS = 0b00,
     FIFO = 0b01,
     STREAM = 0b10,
+    TRIGGER = 0b11,
+}
+
+/// Interrupt line an event is routed to
+/// 
+/// # Variants
+/// 
+/// - `#[default] INT1 = 0b0`
+/// - `INT2 = 0b1`
+#[derive(Default, Specifier, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InterruptPin{
+    #[default]
+    INT1 = 0b0,
+    INT2 = 0b1,
+}
+
+/// Bit field for the (read only) FIFO_STATUS register
+/// 
+/// # Fields
+/// 
+/// - `entries` (`B6`) - number of samples currently stored in the FIFO
+/// - `#[skip] __` (`B1`)
+/// - `fifo_trig` (`B1`) - 1 when a trigger event has occurred (trigger mode only)
+#[derive(Clone, Copy)]
+#[bitfield(bits = 8)]
+pub struct FIFO_STATUS{
+    pub entries: B6,
+    #[skip]
+    __: B1,
+    pub fifo_trig: B1
+}
+
+impl FIFO_STATUS  {
+    pub fn address(&self) -> u8 {
+        FIFO_STATUS_ADDR
+    }
+}
+
+impl Default for FIFO_STATUS {
+    fn default() -> Self {
+        FIFO_STATUS::new()
+    }
+}
+
+/// Tap detection axis enables
+/// 
+/// # Fields
+/// 
+/// - `tap_z_enable` (`B1`) - 1 enables z-axis participation in tap detection
+/// - `tap_y_enable` (`B1`) - 1 enables y-axis participation in tap detection
+/// - `tap_x_enable` (`B1`) - 1 enables x-axis participation in tap detection
+/// - `suppress` (`B1`) - 1 suppresses double tap detection if acceleration greater than 
+/// THRESH_TAP is present between taps
+/// - `#[skip] __` (`B4`)
+#[derive(Clone, Copy)]
+#[bitfield(bits = 8)]
+pub struct TAP_AXES{
+    pub tap_z_enable: B1,
+    pub tap_y_enable: B1,
+    pub tap_x_enable: B1,
+    pub suppress: B1,
+    #[skip]
+    __: B4,
+}
+
+impl TAP_AXES  {
+    pub fn address(&self) -> u8 {
+        TAP_AXES_ADDR
+    }
+}
+
+impl Default for TAP_AXES {
+    fn default() -> Self {
+        TAP_AXES::new()
+    }
+}
+
+/// Bit field for the (read only) ACT_TAP_STATUS register. The source bits indicate the first
+/// axis involved in a tap or activity event, and are overwritten (not cleared) by the next event
+/// 
+/// # Fields
+/// 
+/// - `tap_z_source` (`B1`)
+/// - `tap_y_source` (`B1`)
+/// - `tap_x_source` (`B1`)
+/// - `asleep` (`B1`) - 1 when the device is asleep
+/// - `act_z_source` (`B1`)
+/// - `act_y_source` (`B1`)
+/// - `act_x_source` (`B1`)
+/// - `#[skip] __` (`B1`)
+#[derive(Clone, Copy)]
+#[bitfield(bits = 8)]
+pub struct ACT_TAP_STATUS{
+    pub tap_z_source: B1,
+    pub tap_y_source: B1,
+    pub tap_x_source: B1,
+    pub asleep: B1,
+    pub act_z_source: B1,
+    pub act_y_source: B1,
+    pub act_x_source: B1,
+    #[skip]
+    __: B1,
+}
+
+impl ACT_TAP_STATUS  {
+    pub fn address(&self) -> u8 {
+        ACT_TAP_STATUS_ADDR
+    }
+}
+
+impl Default for ACT_TAP_STATUS {
+    fn default() -> Self {
+        ACT_TAP_STATUS::new()
+    }
+}
+
+/// Activity and inactivity detection control
+/// 
+/// # Fields
+/// 
+/// - `inact_z_enable` (`B1`)
+/// - `inact_y_enable` (`B1`)
+/// - `inact_x_enable` (`B1`)
+/// - `inact_ac_dc` (`Coupling`)
+/// - `act_z_enable` (`B1`)
+/// - `act_y_enable` (`B1`)
+/// - `act_x_enable` (`B1`)
+/// - `act_ac_dc` (`Coupling`)
+#[derive(Clone, Copy)]
+#[bitfield(bits = 8)]
+pub struct ACT_INACT_CTL{
+    pub inact_z_enable: B1,
+    pub inact_y_enable: B1,
+    pub inact_x_enable: B1,
+    pub inact_ac_dc: Coupling,
+    pub act_z_enable: B1,
+    pub act_y_enable: B1,
+    pub act_x_enable: B1,
+    pub act_ac_dc: Coupling,
+}
+
+impl ACT_INACT_CTL  {
+    pub fn address(&self) -> u8 {
+        ACT_INACT_CTL_ADDR
+    }
+}
+
+impl Default for ACT_INACT_CTL {
+    fn default() -> Self {
+        ACT_INACT_CTL::new()
+    }
+}
+
+/// Reference used by the activity and inactivity functions
+/// 
+/// # Variants
+/// 
+/// - `#[default] DC = 0b0` - acceleration is compared directly to the threshold
+/// - `AC = 0b1` - acceleration is compared relative to a reference taken at the start of detection
+#[derive(Default, Specifier, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Coupling {
+    #[default]
+    DC = 0b0,
+    AC = 0b1
+}
+
+/// Bit field for the INT_ENABLE register. 1 enables the function to generate interrupts
+/// 
+/// # Fields
+/// 
+/// - `overrun` (`B1`)
+/// - `watermark` (`B1`)
+/// - `free_fall` (`B1`)
+/// - `inactivity` (`B1`)
+/// - `activity` (`B1`)
+/// - `double_tap` (`B1`)
+/// - `single_tap` (`B1`)
+/// - `data_ready` (`B1`)
+#[derive(Clone, Copy)]
+#[bitfield(bits = 8)]
+pub struct INT_ENABLE{
+    pub overrun: B1,
+    pub watermark: B1,
+    pub free_fall: B1,
+    pub inactivity: B1,
+    pub activity: B1,
+    pub double_tap: B1,
+    pub single_tap: B1,
+    pub data_ready: B1,
+}
+
+impl INT_ENABLE  {
+    pub fn address(&self) -> u8 {
+        INT_ENABLE_ADDR
+    }
+}
+
+impl Default for INT_ENABLE {
+    fn default() -> Self {
+        INT_ENABLE::new()
+    }
+}
+
+/// Bit field for the INT_MAP register. 0 routes the function's interrupt to the INT1 pin, 1 routes
+/// it to the INT2 pin
+/// 
+/// # Fields
+/// 
+/// - `overrun` (`B1`)
+/// - `watermark` (`B1`)
+/// - `free_fall` (`B1`)
+/// - `inactivity` (`B1`)
+/// - `activity` (`B1`)
+/// - `double_tap` (`B1`)
+/// - `single_tap` (`B1`)
+/// - `data_ready` (`B1`)
+#[derive(Clone, Copy)]
+#[bitfield(bits = 8)]
+pub struct INT_MAP{
+    pub overrun: B1,
+    pub watermark: B1,
+    pub free_fall: B1,
+    pub inactivity: B1,
+    pub activity: B1,
+    pub double_tap: B1,
+    pub single_tap: B1,
+    pub data_ready: B1,
+}
+
+impl INT_MAP  {
+    pub fn address(&self) -> u8 {
+        INT_MAP_ADDR
+    }
+}
+
+impl Default for INT_MAP {
+    fn default() -> Self {
+        INT_MAP::new()
+    }
+}
+
+/// Bit field for the (read only) INT_SOURCE register. 1 indicates that the function has 
+/// triggered an event
+/// 
+/// # Fields
+/// 
+/// - `overrun` (`B1`)
+/// - `watermark` (`B1`)
+/// - `free_fall` (`B1`)
+/// - `inactivity` (`B1`)
+/// - `activity` (`B1`)
+/// - `double_tap` (`B1`)
+/// - `single_tap` (`B1`)
+/// - `data_ready` (`B1`)
+#[derive(Clone, Copy)]
+#[bitfield(bits = 8)]
+pub struct INT_SOURCE{
+    pub overrun: B1,
+    pub watermark: B1,
+    pub free_fall: B1,
+    pub inactivity: B1,
+    pub activity: B1,
+    pub double_tap: B1,
+    pub single_tap: B1,
+    pub data_ready: B1,
+}
+
+impl INT_SOURCE  {
+    pub fn address(&self) -> u8 {
+        INT_SOURCE_ADDR
+    }
+}
+
+impl Default for INT_SOURCE {
+    fn default() -> Self {
+        INT_SOURCE::new()
+    }
 }
 
 /// Bit field for the DATA_Format register. Configures the range, justification, and resolution
@@ -173,8 +555,8 @@ pub enum FIFOMode{
 /// - `full_res` (`FullRes`)
 /// - `#[skip] __` (`B1`) 
 /// - `#[skip] int_invert` (`B1`) 
-/// - `#[skip] spi_mode` (`B1`) 
-/// - `#[skip] self_test` (`B1`)
+/// - `spi_mode` (`B1`) - 1 selects 3-wire SPI mode, 0 selects 4-wire SPI mode
+/// - `self_test` (`B1`) - 1 applies a self-test force to the sensor, shifting the output data
 #[bitfield(bits = 8)]
 pub struct DATA_FORMAT{
     pub range: AccelRange,
@@ -187,11 +569,9 @@ pub struct DATA_FORMAT{
     #[skip]
     int_invert: B1,
 
-    #[skip]
     pub spi_mode: B1,
 
-    #[skip]
-    self_test: B1
+    pub self_test: B1
 }
 
 impl DATA_FORMAT {
@@ -213,7 +593,7 @@ impl Default for DATA_FORMAT {
 /// - `_4g = 0b01` 
 /// - `_8g = 0b10` 
 /// - `_16g = 0b11`
-#[derive(Default, Specifier, Clone, Copy, PartialEq, Eq)]
+#[derive(Default, Specifier, Clone, Copy, PartialEq, Eq, Debug)]
 pub enum AccelRange{
     #[default]
     _2g = 0b00,
@@ -237,7 +617,7 @@ pub enum Alignment {
 /// 
 /// - `#[default] _10bit_res = 0b0` - Describe this variant.
 /// - `full_res = 0b1` - Describe this variant.
-#[derive(Default, Specifier, Clone, Copy)]
+#[derive(Default, Specifier, Clone, Copy, PartialEq, Eq, Debug)]
 pub enum FullRes {
     #[default]
     _10bit_res = 0b0,
@@ -272,6 +652,14 @@ mod tests {
             POWER_CTL::default().with_measure(0b1).into_bytes()[0],
             0b1 << 3
         );
+        assert_eq!(
+            POWER_CTL::default()
+            .with_link(0b1)
+            .with_autosleep(0b1)
+            .with_sleep(0b1)
+            .with_wakeup(SLEEP_MODE_ODR::_2Hz).into_bytes()[0],
+            (0b1 << 5) | (0b1 << 4) | (0b1 << 2) | 0b10
+        );
     }
 
     #[test]
@@ -285,6 +673,87 @@ mod tests {
         );
     }
 
+    #[test]
+    fn fifo_ctl_configs(){
+        assert_eq!(FIFO_CTL::default().into_bytes()[0], 0b00000000);
+        assert_eq!(
+            FIFO_CTL::default()
+            .with_fifo_mode(FIFOMode::TRIGGER)
+            .with_trigger(InterruptPin::INT2)
+            .with_samples(0x1F).into_bytes()[0],
+            (0b11 << 6) | (0b1 << 5) | 0x1F
+        );
+    }
+
+    #[test]
+    fn fifo_status_decode(){
+        let status = FIFO_STATUS::from_bytes([(0b1 << 7) | 0x21]);
+        assert_eq!(status.entries(), 33);
+        assert_eq!(status.fifo_trig(), 1);
+    }
+
+    #[test]
+    fn tap_registers(){
+        assert_eq!(
+            TAP_AXES::default().with_suppress(0b1).with_tap_x_enable(0b1).into_bytes()[0],
+            (0b1 << 3) | (0b1 << 2)
+        );
+        let status = ACT_TAP_STATUS::from_bytes([0b0000_0010]);
+        assert_eq!((status.tap_x_source(), status.tap_y_source(), status.tap_z_source()), (0, 1, 0));
+        let source = INT_SOURCE::from_bytes([0b0110_0000]);
+        assert_eq!((source.single_tap(), source.double_tap(), source.data_ready()), (1, 1, 0));
+    }
+
+    #[test]
+    fn act_inact_ctl_configs(){
+        assert_eq!(
+            ACT_INACT_CTL::default()
+            .with_act_ac_dc(Coupling::AC)
+            .with_act_x_enable(0b1)
+            .with_inact_z_enable(0b1).into_bytes()[0],
+            (0b1 << 7) | (0b1 << 6) | 0b1
+        );
+    }
+
+    #[test]
+    fn odr_helpers(){
+        assert_eq!(OutputDataRate::Hz3200.hz(), 3200.0);
+        assert_eq!(OutputDataRate::Hz100.hz(), 100.0);
+        assert_eq!(OutputDataRate::Hz3_13.hz(), 3.125);
+        assert_eq!(OutputDataRate::Hz0_10.hz(), 0.09765625);
+        assert_eq!(OutputDataRate::Hz800.bandwidth_hz(), 400.0);
+        assert_eq!(OutputDataRate::Hz3200.period(), Duration::from_nanos(312_500));
+        assert_eq!(OutputDataRate::Hz100.period(), Duration::from_millis(10));
+        assert_eq!(OutputDataRate::Hz0_10.period(), Duration::from_millis(10_240));
+
+        assert_eq!(OutputDataRate::Hz1600.normal_current_ua(), 90);
+        assert_eq!(OutputDataRate::Hz12_5.low_power_current_ua(), Some(34));
+        assert_eq!(OutputDataRate::Hz800.low_power_current_ua(), None);
+        assert_eq!(
+            OutputDataRate::ALL.iter().filter(|odr| odr.supports_low_power()).count(),
+            OutputDataRate::ALL.iter().filter(|odr| odr.low_power_current_ua().is_some()).count()
+        );
+    }
+
+    #[test]
+    fn odr_from_hz(){
+        for odr in OutputDataRate::ALL {
+            assert_eq!(OutputDataRate::from_hz(odr.hz()), Some(odr));
+            assert_eq!(OutputDataRate::nearest(odr.hz()), odr);
+        }
+        assert_eq!(OutputDataRate::from_hz(800.0), Some(OutputDataRate::Hz800));
+        assert_eq!(OutputDataRate::from_hz(3.13), Some(OutputDataRate::Hz3_13));
+        assert_eq!(OutputDataRate::from_hz(0.10), Some(OutputDataRate::Hz0_10));
+        assert_eq!(OutputDataRate::from_hz(0.20), Some(OutputDataRate::Hz0_20));
+        assert_eq!(OutputDataRate::from_hz(1000.0), None);
+
+        assert_eq!(OutputDataRate::nearest(1000.0), OutputDataRate::Hz800);
+        assert_eq!(OutputDataRate::nearest(0.15), OutputDataRate::Hz0_20);
+        assert_eq!(OutputDataRate::nearest(10_000.0), OutputDataRate::Hz3200);
+        assert_eq!(OutputDataRate::nearest(0.0), OutputDataRate::Hz0_10);
+        assert_eq!(OutputDataRate::nearest(f32::NAN), OutputDataRate::Hz0_10);
+    }
+
     
 }
 
@@ -0,0 +1,353 @@
+//! Snapshot of the register map from THRESH_TAP (0x1D) to FIFO_STATUS (0x39), for field debugging
+
+use core::fmt::{self, Display, Formatter, Write};
+
+use super::{
+    THRESH_TAP_ADDR, FIFO_STATUS_ADDR, OFSX_ADDR, OFSY_ADDR, OFSZ_ADDR, DUR_ADDR, LATENT_ADDR, WINDOW_ADDR,
+    THRESH_ACT_ADDR, THRESH_INACT_ADDR, TIME_INACT_ADDR, ACT_INACT_CTL_ADDR, THRESH_FF_ADDR, TIME_FF_ADDR,
+    TAP_AXES_ADDR, ACT_TAP_STATUS_ADDR, BW_RATE_ADDR, POWER_CTL_ADDR, INT_ENABLE_ADDR, INT_MAP_ADDR,
+    INT_SOURCE_ADDR, DATA_FORMAT_ADDR, DATAX0_ADDR, DATAY0_ADDR, DATAZ0_ADDR, FIFO_CTL_ADDR,
+    accel_configs::{
+        BW_RATE, POWER_CTL, DATA_FORMAT, FIFO_CTL, FIFO_STATUS, TAP_AXES, ACT_TAP_STATUS, ACT_INACT_CTL
+    }
+};
+use crate::utils::{
+    settings::ADXL343Settings,
+    interrupts::InterruptSet,
+    offsets::OFS_G_PER_LSB,
+    tap::{THRESH_TAP_G_PER_LSB, DUR_MS_PER_LSB, LATENT_MS_PER_LSB, WINDOW_MS_PER_LSB},
+    free_fall::TIME_FF_MS_PER_LSB,
+};
+
+/// number of registers from THRESH_TAP (0x1D) to FIFO_STATUS (0x39)
+pub const DUMP_LEN: usize = (FIFO_STATUS_ADDR - THRESH_TAP_ADDR + 1) as usize;
+
+const REGISTER_NAMES: [&str; DUMP_LEN] = [
+    "THRESH_TAP", "OFSX", "OFSY", "OFSZ", "DUR", "LATENT", "WINDOW", "THRESH_ACT", "THRESH_INACT",
+    "TIME_INACT", "ACT_INACT_CTL", "THRESH_FF", "TIME_FF", "TAP_AXES", "ACT_TAP_STATUS", "BW_RATE",
+    "POWER_CTL", "INT_ENABLE", "INT_MAP", "INT_SOURCE", "DATA_FORMAT", "DATAX0", "DATAX1", "DATAY0",
+    "DATAY1", "DATAZ0", "DATAZ1", "FIFO_CTL", "FIFO_STATUS",
+];
+
+const INTERRUPT_NAMES: [(InterruptSet, &str); 8] = [
+    (InterruptSet::DATA_READY, "DATA_READY"),
+    (InterruptSet::SINGLE_TAP, "SINGLE_TAP"),
+    (InterruptSet::DOUBLE_TAP, "DOUBLE_TAP"),
+    (InterruptSet::ACTIVITY, "ACTIVITY"),
+    (InterruptSet::INACTIVITY, "INACTIVITY"),
+    (InterruptSet::FREE_FALL, "FREE_FALL"),
+    (InterruptSet::WATERMARK, "WATERMARK"),
+    (InterruptSet::OVERRUN, "OVERRUN"),
+];
+
+/// datasheet name of the register at address, None outside of 0x1D - 0x39
+pub fn register_name(address: u8) -> Option<&'static str> {
+    REGISTER_NAMES.get(address.checked_sub(THRESH_TAP_ADDR)? as usize).copied()
+}
+
+/// Values of registers 0x1D - 0x39, as returned by ADXL343Interface::dump_registers.
+/// Display prints one register per line with its address, name, value and decoded fields
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RegisterDump {
+    values: [u8; DUMP_LEN]
+}
+
+/// Register whose value differs between two dumps
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RegisterChange {
+    pub address: u8,
+    pub name: &'static str,
+    pub before: u8,
+    pub after: u8
+}
+
+impl RegisterDump {
+    /// values holds the registers from THRESH_TAP (0x1D) to FIFO_STATUS (0x39), in address order
+    pub fn new(values: [u8; DUMP_LEN]) -> Self {
+        RegisterDump { values }
+    }
+
+    pub fn values(&self) -> &[u8; DUMP_LEN] {
+        &self.values
+    }
+
+    /// value of the register at address, None outside of 0x1D - 0x39
+    pub fn get(&self, address: u8) -> Option<u8> {
+        self.values.get(address.checked_sub(THRESH_TAP_ADDR)? as usize).copied()
+    }
+
+    /// (address, value) of every register in the dump
+    pub fn iter(&self) -> impl Iterator<Item = (u8, u8)> + '_ {
+        self.values.iter().enumerate().map(|(offset, value)| (THRESH_TAP_ADDR + offset as u8, *value))
+    }
+
+    fn reg(&self, address: u8) -> u8 {
+        self.values[(address - THRESH_TAP_ADDR) as usize]
+    }
+
+    pub fn bw_rate(&self) -> BW_RATE {
+        BW_RATE::from_bytes([self.reg(BW_RATE_ADDR)])
+    }
+
+    pub fn power_ctl(&self) -> POWER_CTL {
+        POWER_CTL::from_bytes([self.reg(POWER_CTL_ADDR)])
+    }
+
+    pub fn data_format(&self) -> DATA_FORMAT {
+        DATA_FORMAT::from_bytes([self.reg(DATA_FORMAT_ADDR)])
+    }
+
+    pub fn fifo_ctl(&self) -> FIFO_CTL {
+        FIFO_CTL::from_bytes([self.reg(FIFO_CTL_ADDR)])
+    }
+
+    pub fn fifo_status(&self) -> FIFO_STATUS {
+        FIFO_STATUS::from_bytes([self.reg(FIFO_STATUS_ADDR)])
+    }
+
+    pub fn tap_axes(&self) -> TAP_AXES {
+        TAP_AXES::from_bytes([self.reg(TAP_AXES_ADDR)])
+    }
+
+    pub fn act_tap_status(&self) -> ACT_TAP_STATUS {
+        ACT_TAP_STATUS::from_bytes([self.reg(ACT_TAP_STATUS_ADDR)])
+    }
+
+    pub fn act_inact_ctl(&self) -> ACT_INACT_CTL {
+        ACT_INACT_CTL::from_bytes([self.reg(ACT_INACT_CTL_ADDR)])
+    }
+
+    pub fn int_enable(&self) -> InterruptSet {
+        InterruptSet::from_bits(self.reg(INT_ENABLE_ADDR))
+    }
+
+    pub fn int_map(&self) -> InterruptSet {
+        InterruptSet::from_bits(self.reg(INT_MAP_ADDR))
+    }
+
+    pub fn int_source(&self) -> InterruptSet {
+        InterruptSet::from_bits(self.reg(INT_SOURCE_ADDR))
+    }
+
+    /// OFSX, OFSY and OFSZ register values
+    pub fn offsets(&self) -> [i8; 3] {
+        [self.reg(OFSX_ADDR) as i8, self.reg(OFSY_ADDR) as i8, self.reg(OFSZ_ADDR) as i8]
+    }
+
+    /// DATAX0 - DATAZ1, as returned by read_full_sample
+    pub fn sample(&self) -> [u8; 6] {
+        let start = (DATAX0_ADDR - THRESH_TAP_ADDR) as usize;
+        let mut sample = [0u8; 6];
+        sample.copy_from_slice(&self.values[start..start + 6]);
+        sample
+    }
+
+    /// settings the device was configured with, see ADXL343Settings::from_register_values
+    pub fn settings(&self) -> ADXL343Settings {
+        ADXL343Settings::from_register_values(|address| self.reg(address))
+    }
+
+    /// registers whose value differs in other, in address order
+    pub fn diff<'a>(&'a self, other: &'a RegisterDump) -> impl Iterator<Item = RegisterChange> + 'a {
+        self.iter().zip(other.iter()).filter(|((_, before), (_, after))| before != after)
+            .map(|((address, before), (_, after))| RegisterChange {
+                address,
+                name: REGISTER_NAMES[(address - THRESH_TAP_ADDR) as usize],
+                before,
+                after
+            })
+    }
+
+    /// writes the table row of the register at address: address, name, value and decoded fields
+    fn fmt_row(&self, address: u8, f: &mut impl Write) -> fmt::Result {
+        let value = self.reg(address);
+        write!(f, "0x{:02X}  {:<14}  0x{:02X}   {:08b}   ", address, REGISTER_NAMES[(address - THRESH_TAP_ADDR) as usize],
+            value, value)?;
+        self.fmt_decoded(address, f)
+    }
+
+    /// writes the decoded fields of the register at address
+    fn fmt_decoded(&self, address: u8, f: &mut impl Write) -> fmt::Result {
+        let value = self.reg(address);
+        match address {
+            THRESH_TAP_ADDR | THRESH_ACT_ADDR | THRESH_INACT_ADDR | THRESH_FF_ADDR => {
+                write!(f, "{:.4} g", value as f32 * THRESH_TAP_G_PER_LSB)
+            },
+            OFSX_ADDR | OFSY_ADDR | OFSZ_ADDR => write!(f, "{:+.4} g", value as i8 as f32 * OFS_G_PER_LSB),
+            DUR_ADDR => write!(f, "{:.3} ms", value as f32 * DUR_MS_PER_LSB),
+            LATENT_ADDR => write!(f, "{:.2} ms", value as f32 * LATENT_MS_PER_LSB),
+            WINDOW_ADDR => write!(f, "{:.2} ms", value as f32 * WINDOW_MS_PER_LSB),
+            TIME_INACT_ADDR => write!(f, "{} s", value),
+            TIME_FF_ADDR => write!(f, "{} ms", value as f32 * TIME_FF_MS_PER_LSB),
+            ACT_INACT_CTL_ADDR => {
+                let ctl = self.act_inact_ctl();
+                write!(f, "act {:?} x={} y={} z={}, inact {:?} x={} y={} z={}",
+                    ctl.act_ac_dc(), ctl.act_x_enable(), ctl.act_y_enable(), ctl.act_z_enable(),
+                    ctl.inact_ac_dc(), ctl.inact_x_enable(), ctl.inact_y_enable(), ctl.inact_z_enable())
+            },
+            TAP_AXES_ADDR => {
+                let axes = self.tap_axes();
+                write!(f, "x={} y={} z={} suppress={}",
+                    axes.tap_x_enable(), axes.tap_y_enable(), axes.tap_z_enable(), axes.suppress())
+            },
+            ACT_TAP_STATUS_ADDR => {
+                let status = self.act_tap_status();
+                write!(f, "act x={} y={} z={}, tap x={} y={} z={}, asleep={}",
+                    status.act_x_source(), status.act_y_source(), status.act_z_source(),
+                    status.tap_x_source(), status.tap_y_source(), status.tap_z_source(), status.asleep())
+            },
+            BW_RATE_ADDR => {
+                let bw_rate = self.bw_rate();
+                write!(f, "odr={:?} low_power={}", bw_rate.odr(), bw_rate.low_power())
+            },
+            POWER_CTL_ADDR => {
+                let power_ctl = self.power_ctl();
+                write!(f, "wakeup={:?} sleep={} measure={} autosleep={} link={}", power_ctl.wakeup(),
+                    power_ctl.sleep(), power_ctl.measure(), power_ctl.autosleep(), power_ctl.link())
+            },
+            INT_ENABLE_ADDR | INT_MAP_ADDR | INT_SOURCE_ADDR => {
+                let interrupts = InterruptSet::from_bits(value);
+                let mut separator = "";
+                for (interrupt, name) in INTERRUPT_NAMES {
+                    if interrupts.contains(interrupt) {
+                        write!(f, "{}{}", separator, name)?;
+                        separator = " ";
+                    }
+                }
+                Ok(())
+            },
+            DATA_FORMAT_ADDR => {
+                let data_format = self.data_format();
+                write!(f, "range={:?} justify={:?} full_res={:?} spi={} self_test={}", data_format.range(),
+                    data_format.justisfy(), data_format.full_res(), data_format.spi_mode(), data_format.self_test())
+            },
+            DATAX0_ADDR | DATAY0_ADDR | DATAZ0_ADDR => {
+                let axis = ((address - DATAX0_ADDR) / 2) as usize;
+                write!(f, "{} LSB", self.settings().sample_to_raw_axes(self.sample())[axis])
+            },
+            FIFO_CTL_ADDR => {
+                let fifo_ctl = self.fifo_ctl();
+                write!(f, "mode={:?} trigger={:?} samples={}", fifo_ctl.fifo_mode(), fifo_ctl.trigger(), fifo_ctl.samples())
+            },
+            FIFO_STATUS_ADDR => {
+                let status = self.fifo_status();
+                write!(f, "entries={} fifo_trig={}", status.entries(), status.fifo_trig())
+            },
+            _ => Ok(())
+        }
+    }
+}
+
+const TABLE_HEADER: &str = "ADDR  REGISTER        VALUE  BINARY     DECODED";
+
+impl Display for RegisterDump {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", TABLE_HEADER)?;
+        for (address, _) in self.iter() {
+            self.fmt_row(address, f)?;
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// fixed-capacity buffer holding one table row, as defmt cannot print core::fmt output directly.
+/// Rows longer than the buffer are truncated
+#[cfg(feature = "defmt")]
+struct RowBuffer {
+    bytes: [u8; 128],
+    len: usize
+}
+
+#[cfg(feature = "defmt")]
+impl Write for RowBuffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let count = s.len().min(self.bytes.len() - self.len);
+        self.bytes[self.len..self.len + count].copy_from_slice(&s.as_bytes()[..count]);
+        self.len += count;
+        Ok(())
+    }
+}
+
+/// prints the same decoded table as Display, one row at a time
+#[cfg(feature = "defmt")]
+impl defmt::Format for RegisterDump {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=str}\n", TABLE_HEADER);
+        for (address, _) in self.iter() {
+            let mut row = RowBuffer { bytes: [0; 128], len: 0 };
+            let _ = self.fmt_row(address, &mut row);
+            //the rows are ascii, so truncating cannot split a character
+            let row = core::str::from_utf8(&row.bytes[..row.len]).unwrap_or_default();
+            defmt::write!(f, "{=str}\n", row);
+        }
+    }
+}
+
+impl Display for RegisterChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:02X} {}: 0x{:02X} -> 0x{:02X}", self.address, self.name, self.before, self.after)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for RegisterChange {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=u8:#04x} {=str}: {=u8:#04x} -> {=u8:#04x}", self.address, self.name, self.before, self.after);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use std::{format, string::ToString, vec::Vec};
+    use crate::registers::accel_configs::{OutputDataRate, FIFOMode};
+    use super::*;
+
+    fn default_dump() -> RegisterDump {
+        let mut values = [0u8; DUMP_LEN];
+        values[(BW_RATE_ADDR - THRESH_TAP_ADDR) as usize] = 0x0A;
+        values[(INT_SOURCE_ADDR - THRESH_TAP_ADDR) as usize] = 0x82;
+        RegisterDump::new(values)
+    }
+
+    #[test]
+    fn register_names(){
+        assert_eq!(register_name(THRESH_TAP_ADDR), Some("THRESH_TAP"));
+        assert_eq!(register_name(DATA_FORMAT_ADDR), Some("DATA_FORMAT"));
+        assert_eq!(register_name(FIFO_STATUS_ADDR), Some("FIFO_STATUS"));
+        assert_eq!(register_name(0x00), None);
+        assert_eq!(register_name(0x3A), None);
+    }
+
+    #[test]
+    fn typed_decode(){
+        let dump = default_dump();
+        assert_eq!(dump.get(BW_RATE_ADDR), Some(0x0A));
+        assert_eq!(dump.bw_rate().odr(), OutputDataRate::Hz100);
+        assert_eq!(dump.int_source(), InterruptSet::DATA_READY | InterruptSet::WATERMARK);
+        assert_eq!(dump.fifo_ctl().fifo_mode(), FIFOMode::BYPASS);
+        assert_eq!(dump.settings().get_odr(), OutputDataRate::Hz100);
+    }
+
+    #[test]
+    fn display_table(){
+        let table = default_dump().to_string();
+        assert_eq!(table.lines().count(), DUMP_LEN + 1);
+        assert!(table.contains("0x2C  BW_RATE         0x0A   00001010   odr=Hz100 low_power=0"));
+        assert!(table.contains("0x30  INT_SOURCE      0x82   10000010   DATA_READY WATERMARK"));
+    }
+
+    #[test]
+    fn dump_diff(){
+        let before = default_dump();
+        let mut values = *before.values();
+        values[(POWER_CTL_ADDR - THRESH_TAP_ADDR) as usize] = 0x08;
+        let after = RegisterDump::new(values);
+
+        assert_eq!(before.diff(&before).count(), 0);
+        let changes: Vec<RegisterChange> = before.diff(&after).collect();
+        assert_eq!(changes, [RegisterChange { address: POWER_CTL_ADDR, name: "POWER_CTL", before: 0x00, after: 0x08 }]);
+        assert_eq!(format!("{}", changes[0]), "0x2D POWER_CTL: 0x00 -> 0x08");
+    }
+}
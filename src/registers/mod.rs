@@ -36,10 +36,46 @@ pub const FIFO_CTL_ADDR: u8       = 0x38;
 pub const FIFO_STATUS_ADDR: u8    = 0x39;
 
 pub const REGISTER_SIZE: u8 = 8;
-pub const ADXL343_ADDR: u8 = 0x53; //i2c slave device address when using a qwiic connector
+pub const ADXL343_ADDR: u8 = 0x53; //i2c slave device address when using a qwiic connector (ALT ADDRESS low)
+pub const ADXL343_ALT_ADDR: u8 = 0x1D; //i2c slave device address with ALT ADDRESS (pin 12) high
 pub const DEVID_REG_VALUE: u8 = 0xE5;
+pub const FIFO_SAMPLES_MAX: u8 = 0x1F; //largest watermark the FIFO_CTL samples field can hold
+pub const FIFO_ENTRIES_MAX: usize = 33; //32 FIFO samples plus the one held in the data registers
 //registers for data rate, power saving modes, justification
 pub mod accel_configs; 
+pub mod shadow;
+pub mod dump;
+
+const REGISTER_WRITES_CAPACITY: usize = 24;
+
+/// Ordered list of (register address, value) writes. Built from the settings, so the blocking
+/// and async drivers perform exactly the same register writes
+#[derive(Clone, Copy)]
+pub(crate) struct RegisterWrites {
+    writes: [(u8, u8); REGISTER_WRITES_CAPACITY],
+    len: usize
+}
+
+impl RegisterWrites {
+    pub(crate) fn new() -> Self {
+        RegisterWrites { writes: [(0, 0); REGISTER_WRITES_CAPACITY], len: 0 }
+    }
+
+    pub(crate) fn push(&mut self, address: u8, value: u8) {
+        self.writes[self.len] = (address, value);
+        self.len += 1;
+    }
+
+    pub(crate) fn extend(&mut self, writes: &[(u8, u8)]) {
+        for &(address, value) in writes {
+            self.push(address, value);
+        }
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (u8, u8)> + '_ {
+        self.writes[..self.len].iter().copied()
+    }
+}
 
 
 
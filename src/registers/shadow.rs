@@ -0,0 +1,124 @@
+//! Shadow copy of the writable registers, recording the last value the driver wrote to each
+
+use super::{
+    THRESH_TAP_ADDR, FIFO_CTL_ADDR, ACT_TAP_STATUS_ADDR, INT_SOURCE_ADDR, DATAX0_ADDR, DATAZ1_ADDR, DATA_FORMAT_ADDR,
+    TAP_AXES_ADDR, BW_RATE_ADDR, INT_MAP_ADDR
+};
+
+/// number of registers from THRESH_TAP (0x1D) to FIFO_CTL (0x38)
+const SHADOW_LEN: usize = (FIFO_CTL_ADDR - THRESH_TAP_ADDR + 1) as usize;
+
+/// Last value written to each writable register (THRESH_TAP - TAP_AXES, BW_RATE - INT_MAP, DATA_FORMAT
+/// and FIFO_CTL). Registers the driver has not written yet hold None
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RegisterShadow {
+    values: [Option<u8>; SHADOW_LEN]
+}
+
+impl RegisterShadow {
+    pub fn new() -> Self {
+        RegisterShadow { values: [None; SHADOW_LEN] }
+    }
+
+    /// true for the registers the device allows writing to
+    pub fn is_writable(address: u8) -> bool {
+        (THRESH_TAP_ADDR..=FIFO_CTL_ADDR).contains(&address)
+            && address != ACT_TAP_STATUS_ADDR
+            && address != INT_SOURCE_ADDR
+            && !(DATAX0_ADDR..=DATAZ1_ADDR).contains(&address)
+    }
+
+    /// stores value as the expected content of address (ignored for read-only registers)
+    pub fn record(&mut self, address: u8, value: u8) {
+        if Self::is_writable(address) {
+            self.values[(address - THRESH_TAP_ADDR) as usize] = Some(value);
+        }
+    }
+
+    /// last value written to address, None if it was never written or is read-only
+    pub fn get(&self, address: u8) -> Option<u8> {
+        match Self::is_writable(address) {
+            true => self.values[(address - THRESH_TAP_ADDR) as usize],
+            false => None
+        }
+    }
+
+    /// forgets every recorded value (e.g. after a device reset)
+    pub fn clear(&mut self) {
+        self.values = [None; SHADOW_LEN];
+    }
+
+    /// (address, value) of every recorded register, in address order
+    pub fn iter(&self) -> impl Iterator<Item = (u8, u8)> + '_ {
+        self.values.iter().enumerate()
+            .filter_map(|(offset, value)| value.map(|value| (THRESH_TAP_ADDR + offset as u8, value)))
+    }
+
+    /// first recorded register whose value differs from the registers read back from the device,
+    /// as (address, expected, found). read_back holds the registers starting at THRESH_TAP, in
+    /// address order; registers past its end are not compared
+    pub fn first_mismatch(&self, read_back: &[u8]) -> Option<(u8, u8, u8)> {
+        self.iter().find_map(|(address, expected)| {
+            let found = *read_back.get((address - THRESH_TAP_ADDR) as usize)?;
+            (found != expected).then_some((address, expected, found))
+        })
+    }
+}
+
+/// registers read back by verify_configuration (THRESH_TAP - DATA_FORMAT), stopping before DATAX0 
+/// since reading the data registers pops a FIFO entry. INT_SOURCE (0x30) is not read, as reading it 
+/// clears the tap, activity and free fall flags, and neither is ACT_TAP_STATUS (0x2B); both are 
+/// read-only and left at 0, see VERIFY_BURSTS
+pub(crate) const VERIFY_BURST_LEN: usize = (DATA_FORMAT_ADDR - THRESH_TAP_ADDR + 1) as usize;
+
+/// (first, last) register of each burst filling the VERIFY_BURST_LEN registers, skipping 
+/// ACT_TAP_STATUS and INT_SOURCE
+pub(crate) const VERIFY_BURSTS: [(u8, u8); 3] = [
+    (THRESH_TAP_ADDR, TAP_AXES_ADDR),
+    (BW_RATE_ADDR, INT_MAP_ADDR),
+    (DATA_FORMAT_ADDR, DATA_FORMAT_ADDR)
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registers::{BW_RATE_ADDR, POWER_CTL_ADDR, OFSX_ADDR, DEVID_ADDR, FIFO_STATUS_ADDR};
+
+    #[test]
+    fn writable_registers(){
+        assert!(RegisterShadow::is_writable(THRESH_TAP_ADDR));
+        assert!(RegisterShadow::is_writable(BW_RATE_ADDR));
+        assert!(RegisterShadow::is_writable(DATA_FORMAT_ADDR));
+        assert!(RegisterShadow::is_writable(FIFO_CTL_ADDR));
+        assert!(!RegisterShadow::is_writable(DEVID_ADDR));
+        assert!(!RegisterShadow::is_writable(ACT_TAP_STATUS_ADDR));
+        assert!(!RegisterShadow::is_writable(INT_SOURCE_ADDR));
+        assert!(!RegisterShadow::is_writable(DATAX0_ADDR));
+        assert!(!RegisterShadow::is_writable(FIFO_STATUS_ADDR));
+    }
+
+    #[test]
+    fn record_and_compare(){
+        let mut shadow = RegisterShadow::new();
+        shadow.record(BW_RATE_ADDR, 0x0D);
+        shadow.record(POWER_CTL_ADDR, 0x08);
+        shadow.record(DATAX0_ADDR, 0x12);
+        assert_eq!(shadow.get(BW_RATE_ADDR), Some(0x0D));
+        assert_eq!(shadow.get(OFSX_ADDR), None);
+        assert_eq!(shadow.get(DATAX0_ADDR), None);
+        assert_eq!(shadow.iter().count(), 2);
+
+        let mut read_back = [0u8; VERIFY_BURST_LEN];
+        read_back[(BW_RATE_ADDR - THRESH_TAP_ADDR) as usize] = 0x0D;
+        read_back[(POWER_CTL_ADDR - THRESH_TAP_ADDR) as usize] = 0x08;
+        assert_eq!(shadow.first_mismatch(&read_back), None);
+
+        //brown-out: registers back to their reset values
+        read_back = [0u8; VERIFY_BURST_LEN];
+        read_back[(BW_RATE_ADDR - THRESH_TAP_ADDR) as usize] = 0x0A;
+        assert_eq!(shadow.first_mismatch(&read_back), Some((BW_RATE_ADDR, 0x0D, 0x0A)));
+
+        shadow.clear();
+        assert_eq!(shadow.iter().count(), 0);
+    }
+}
@@ -0,0 +1,234 @@
+//! Typestate wrapper around [`ADXL343Interface`], tracking the device state in the type system.
+//!
+//! [`ADXL343::new`] returns an [`Unconfigured`] handle. `init` consumes it and returns a [`Configured`]
+//! handle, and `begin_measurements` returns a [`Measuring`] handle. Sample reading methods only exist
+//! on the [`Measuring`] state, so reading the data registers before the device is configured and
+//! measuring is a compile error instead of a stale register read.
+//!
+//! ```
+//! let sensor = ADXL343::new(i2c).with_settings(settings)?;
+//! let mut sensor = sensor.init()?.begin_measurements()?;
+//! let accel_data_g: [f32; 3] = sensor.read_accel()?;
+//! let sensor = sensor.turn_off_measurements()?; // back to Configured
+//! ```
+use core::marker::PhantomData;
+use embedded_hal::{i2c::I2c, spi::SpiDevice};
+
+use crate::{
+    adxl343_interface::{ADXL343Interface, ADXL343Error},
+    bus::{RegisterBus, I2cBus, I2cAddress, SpiBus},
+    utils::settings::ADXL343Settings,
+};
+
+/// Settings have not been written to the device
+pub struct Unconfigured;
+
+/// Settings have been written to the device, which is in standby
+pub struct Configured;
+
+/// The device is in measurement mode
+pub struct Measuring;
+
+/// Device handle whose state (Unconfigured, Configured or Measuring) is part of its type
+pub struct ADXL343<B, State>
+where
+    B: RegisterBus,
+{
+    interface: ADXL343Interface<B>,
+    _state: PhantomData<State>,
+}
+
+impl<I> ADXL343<I2cBus<I>, Unconfigured>
+where
+    I: I2c,
+{
+    /// Returns an unconfigured handle with default settings, communicating over I2c with the
+    /// device at the primary address 0x53
+    pub fn new(i2c: I) -> Self {
+        Self::new_with_bus(I2cBus::new(i2c))
+    }
+
+    /// Returns an unconfigured handle with default settings, communicating over I2c with the
+    /// device at the given address
+    pub fn new_with_address(i2c: I, address: I2cAddress) -> Self {
+        Self::new_with_bus(I2cBus::new_with_address(i2c, address))
+    }
+
+    /// Validates the settings for an I2c bus running at bus_speed_hz before writing them to the 
+    /// device, see ADXL343Interface::init_validated
+    pub fn init_validated(mut self, bus_speed_hz: u32) -> Result<ADXL343<I2cBus<I>, Configured>, ADXL343Error<I::Error>> {
+        self.interface.init_validated(bus_speed_hz)?;
+        Ok(self.transition())
+    }
+}
+
+impl<S> ADXL343<SpiBus<S>, Unconfigured>
+where
+    S: SpiDevice,
+{
+    /// Returns an unconfigured handle with default settings, communicating over 4-wire SPI
+    pub fn new_spi(spi: S) -> Self {
+        Self::new_with_bus(SpiBus::new(spi))
+    }
+
+    /// Returns an unconfigured handle with default settings, communicating over 3-wire SPI
+    pub fn new_spi_3wire(spi: S) -> Self {
+        Self::new_with_bus(SpiBus::new_3wire(spi))
+    }
+}
+
+impl<B, State> ADXL343<B, State>
+where
+    B: RegisterBus,
+{
+    fn transition<Next>(self) -> ADXL343<B, Next> {
+        ADXL343 {
+            interface: self.interface,
+            _state: PhantomData,
+        }
+    }
+
+    /// Returns the settings the device has been (or will be) configured with
+    pub fn settings(&self) -> &ADXL343Settings {
+        self.interface.settings()
+    }
+
+    /// Ensures that the device responding on the bus has DEVID 0xE5
+    pub fn confirm_device(&mut self) -> Result<(), ADXL343Error<B::Error>> {
+        self.interface.confirm_device()
+    }
+
+    /// Returns the underlying runtime-checked interface, giving up the typestate guarantees
+    pub fn into_interface(self) -> ADXL343Interface<B> {
+        self.interface
+    }
+}
+
+impl<B> ADXL343<B, Unconfigured>
+where
+    B: RegisterBus,
+{
+    /// Returns an unconfigured handle with default settings, communicating over bus
+    pub fn new_with_bus(bus: B) -> Self {
+        Self {
+            interface: ADXL343Interface::new_with_bus(bus),
+            _state: PhantomData,
+        }
+    }
+
+    /// Replaces the settings written by init. Returns MeasurementModeBeforeConfig when the provided
+    /// settings are in measurement mode (use begin_measurements on the configured handle instead)
+    pub fn with_settings(mut self, settings: ADXL343Settings) -> Result<Self, ADXL343Error<B::Error>> {
+        self.interface.with_settings(settings)?;
+        Ok(self)
+    }
+
+    /// Writes the settings to the device (see ADXL343Interface::init), leaving it in standby
+    pub fn init(mut self) -> Result<ADXL343<B, Configured>, ADXL343Error<B::Error>> {
+        self.interface.init()?;
+        Ok(self.transition())
+    }
+}
+
+impl<B> ADXL343<B, Configured>
+where
+    B: RegisterBus,
+{
+    /// Places the device into measurement mode
+    pub fn begin_measurements(mut self) -> Result<ADXL343<B, Measuring>, ADXL343Error<B::Error>> {
+        self.interface.begin_measurements()?;
+        Ok(self.transition())
+    }
+
+    /// Writes new settings to the device, which stays in standby
+    pub fn reconfigure(self, settings: ADXL343Settings) -> Result<Self, ADXL343Error<B::Error>> {
+        let unconfigured: ADXL343<B, Unconfigured> = self.transition();
+        unconfigured.with_settings(settings)?.init()
+    }
+}
+
+impl<B> ADXL343<B, Measuring>
+where
+    B: RegisterBus,
+{
+    /// Places the device into standby
+    pub fn turn_off_measurements(mut self) -> Result<ADXL343<B, Configured>, ADXL343Error<B::Error>> {
+        self.interface.turn_off_measurements()?;
+        Ok(self.transition())
+    }
+
+    /// Returns raw accelerometer readings in the format:
+    /// [x_low, x_high, y_low, y_high, z_low, z_high]
+    pub fn read_full_sample(&mut self) -> Result<[u8; 6], ADXL343Error<B::Error>> {
+        self.interface.read_full_sample()
+    }
+
+    /// accel reading [x_axis, y_axis, z_axis] in g's
+    pub fn read_accel(&mut self) -> Result<[f32; 3], ADXL343Error<B::Error>> {
+        self.interface.read_accel()
+    }
+
+    /// accel reading [x_axis, y_axis, z_axis] in milli-g's, computed with integer math
+    pub fn read_accel_mg(&mut self) -> Result<[i32; 3], ADXL343Error<B::Error>> {
+        self.interface.read_accel_mg()
+    }
+
+    /// accel reading [x_axis, y_axis, z_axis] in g's as I16F16 fixed point numbers
+    #[cfg(feature = "fixed")]
+    pub fn read_accel_fixed(&mut self) -> Result<[fixed::types::I16F16; 3], ADXL343Error<B::Error>> {
+        self.interface.read_accel_fixed()
+    }
+
+    /// Returns the number of samples currently stored in the FIFO
+    pub fn fifo_entries(&mut self) -> Result<u8, ADXL343Error<B::Error>> {
+        self.interface.fifo_entries()
+    }
+
+    /// Reads up to buffer.len() FIFO entries as raw samples, see ADXL343Interface::read_fifo_samples
+    pub fn read_fifo_samples(&mut self, buffer: &mut [[u8; 6]]) -> Result<usize, ADXL343Error<B::Error>> {
+        self.interface.read_fifo_samples(buffer)
+    }
+
+    /// Drains up to buffer.len() samples from the FIFO, see ADXL343Interface::read_fifo_raw
+    pub fn read_fifo_raw(&mut self, buffer: &mut [[i16; 3]]) -> Result<usize, ADXL343Error<B::Error>> {
+        self.interface.read_fifo_raw(buffer)
+    }
+
+    /// Drains up to buffer.len() samples from the FIFO in g's, see ADXL343Interface::read_fifo_accel
+    pub fn read_fifo_accel(&mut self, buffer: &mut [[f32; 3]]) -> Result<usize, ADXL343Error<B::Error>> {
+        self.interface.read_fifo_accel(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use std::vec;
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+    use crate::registers::{ADXL343_ADDR, POWER_CTL_ADDR, DATAX0_ADDR};
+    use super::*;
+
+    #[test]
+    fn state_transitions(){
+        let settings = ADXL343Settings::default();
+        let mut expectations: std::vec::Vec<I2cTransaction> = settings.init_register_writes().unwrap().iter()
+            .map(|(address, value)| I2cTransaction::write(ADXL343_ADDR, vec![address, value]))
+            .collect();
+        expectations.extend([
+            I2cTransaction::write(ADXL343_ADDR, vec![POWER_CTL_ADDR, 0x08]),
+            I2cTransaction::write_read(ADXL343_ADDR, vec![DATAX0_ADDR], vec![0, 0, 0, 0, 0x00, 0x01]),
+            I2cTransaction::write(ADXL343_ADDR, vec![POWER_CTL_ADDR, 0x00]),
+        ]);
+        let i2c = I2cMock::new(&expectations);
+
+        let sensor = ADXL343::new(i2c).with_settings(settings).unwrap();
+        let mut sensor = sensor.init().unwrap().begin_measurements().unwrap();
+        assert!(sensor.settings().in_measurement_mode());
+        assert_eq!(sensor.read_full_sample().unwrap(), [0, 0, 0, 0, 0x00, 0x01]);
+        let sensor = sensor.turn_off_measurements().unwrap();
+        assert!(!sensor.settings().in_measurement_mode());
+
+        let (mut i2c, _) = sensor.into_interface().destroy();
+        i2c.done();
+    }
+}
@@ -0,0 +1,181 @@
+#![allow(non_snake_case)]
+use derive_setters::Setters;
+
+use crate::registers::{
+    THRESH_ACT_ADDR, THRESH_INACT_ADDR, TIME_INACT_ADDR, ACT_INACT_CTL_ADDR,
+    accel_configs::{ACT_INACT_CTL, ACT_TAP_STATUS, Coupling}
+};
+use super::{axes::AxisSet, interrupts::InterruptSet, to_register_value};
+
+/// THRESH_ACT and THRESH_INACT scale factor (62.5 mg/LSB)
+pub const THRESH_ACT_G_PER_LSB: f32 = 0.0625;
+
+/// Activity and inactivity detection configuration. Thresholds are given in g's and converted
+/// into register values when written to the device.
+/// 
+/// # Fields
+/// 
+/// - `activity_threshold_g` - acceleration which must be exceeded to detect activity (THRESH_ACT)
+/// - `inactivity_threshold_g` - acceleration which must not be exceeded to detect 
+///   inactivity (THRESH_INACT)
+/// - `inactivity_time_s` - time acceleration must stay below inactivity_threshold_g (TIME_INACT)
+/// - `activity_axes` / `inactivity_axes` - axes participating in each function
+/// - `activity_coupling` / `inactivity_coupling` - dc or ac coupled operation of each function
+/// - `activity_interrupt` / `inactivity_interrupt` - enable the ACTIVITY / INACTIVITY interrupts
+#[derive(Clone, Copy, Debug, PartialEq, Setters)]
+pub struct ActivityConfig {
+    pub activity_threshold_g: f32,
+    pub inactivity_threshold_g: f32,
+    pub inactivity_time_s: u8,
+    pub activity_axes: AxisSet,
+    pub inactivity_axes: AxisSet,
+    pub activity_coupling: Coupling,
+    pub inactivity_coupling: Coupling,
+    pub activity_interrupt: bool,
+    pub inactivity_interrupt: bool
+}
+
+impl Default for ActivityConfig {
+    fn default() -> Self {
+        ActivityConfig {
+            activity_threshold_g: 0.25,
+            inactivity_threshold_g: 0.125,
+            inactivity_time_s: 5,
+            activity_axes: AxisSet::ALL,
+            inactivity_axes: AxisSet::ALL,
+            activity_coupling: Coupling::AC,
+            inactivity_coupling: Coupling::AC,
+            activity_interrupt: true,
+            inactivity_interrupt: true
+        }
+    }
+}
+
+impl ActivityConfig {
+    /// THRESH_ACT register value, None if activity_threshold_g is negative or above 15.94 g
+    pub fn THRESH_ACT_reg_value(&self) -> Option<u8> {
+        to_register_value(self.activity_threshold_g, THRESH_ACT_G_PER_LSB)
+    }
+
+    /// THRESH_INACT register value, None if inactivity_threshold_g is negative or above 15.94 g
+    pub fn THRESH_INACT_reg_value(&self) -> Option<u8> {
+        to_register_value(self.inactivity_threshold_g, THRESH_ACT_G_PER_LSB)
+    }
+
+    /// TIME_INACT register value (1 s/LSB)
+    pub fn TIME_INACT_reg_value(&self) -> u8 {
+        self.inactivity_time_s
+    }
+
+    pub fn ACT_INACT_CTL_reg_value(&self) -> u8 {
+        ACT_INACT_CTL::new()
+            .with_act_ac_dc(self.activity_coupling)
+            .with_act_x_enable(self.activity_axes.x as u8)
+            .with_act_y_enable(self.activity_axes.y as u8)
+            .with_act_z_enable(self.activity_axes.z as u8)
+            .with_inact_ac_dc(self.inactivity_coupling)
+            .with_inact_x_enable(self.inactivity_axes.x as u8)
+            .with_inact_y_enable(self.inactivity_axes.y as u8)
+            .with_inact_z_enable(self.inactivity_axes.z as u8).into_bytes()[0]
+    }
+
+    /// decodes the THRESH_ACT, THRESH_INACT, TIME_INACT and ACT_INACT_CTL register values, taking
+    /// the activity_interrupt and inactivity_interrupt flags from the enabled interrupts
+    pub fn from_registers(thresh_act: u8, thresh_inact: u8, time_inact: u8, ctl: ACT_INACT_CTL, 
+        enabled: InterruptSet) -> Self {
+        ActivityConfig {
+            activity_threshold_g: thresh_act as f32 * THRESH_ACT_G_PER_LSB,
+            inactivity_threshold_g: thresh_inact as f32 * THRESH_ACT_G_PER_LSB,
+            inactivity_time_s: time_inact,
+            activity_axes: AxisSet::from_bits(ctl.act_x_enable(), ctl.act_y_enable(), ctl.act_z_enable()),
+            inactivity_axes: AxisSet::from_bits(ctl.inact_x_enable(), ctl.inact_y_enable(), ctl.inact_z_enable()),
+            activity_coupling: ctl.act_ac_dc(),
+            inactivity_coupling: ctl.inact_ac_dc(),
+            activity_interrupt: enabled.contains(InterruptSet::ACTIVITY),
+            inactivity_interrupt: enabled.contains(InterruptSet::INACTIVITY)
+        }
+    }
+
+    /// (address, value) of every activity register, None if a threshold does not fit in its register
+    pub(crate) fn register_writes(&self) -> Option<[(u8, u8); 4]> {
+        Some([
+            (THRESH_ACT_ADDR, self.THRESH_ACT_reg_value()?),
+            (THRESH_INACT_ADDR, self.THRESH_INACT_reg_value()?),
+            (TIME_INACT_ADDR, self.TIME_INACT_reg_value()),
+            (ACT_INACT_CTL_ADDR, self.ACT_INACT_CTL_reg_value())
+        ])
+    }
+
+    /// the interrupts this configuration enables (out of ACTIVITY and INACTIVITY)
+    pub fn interrupts(&self) -> InterruptSet {
+        let mut interrupts = InterruptSet::empty();
+        interrupts.set(InterruptSet::ACTIVITY, self.activity_interrupt);
+        interrupts.set(InterruptSet::INACTIVITY, self.inactivity_interrupt);
+        interrupts
+    }
+}
+
+/// Activity and inactivity state of the device
+/// 
+/// # Fields
+/// 
+/// - `activity` - ACTIVITY flag of INT_SOURCE
+/// - `inactivity` - INACTIVITY flag of INT_SOURCE
+/// - `activity_source` - first axes involved in the last activity event (ACT_X/Y/Z source bits)
+/// - `asleep` - Asleep bit of ACT_TAP_STATUS
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ActivityStatus {
+    pub activity: bool,
+    pub inactivity: bool,
+    pub activity_source: AxisSet,
+    pub asleep: bool
+}
+
+impl ActivityStatus {
+    /// Decodes INT_SOURCE's activity flags and ACT_TAP_STATUS
+    pub fn from_registers(activity: u8, inactivity: u8, status: ACT_TAP_STATUS) -> Self {
+        ActivityStatus {
+            activity: activity != 0,
+            inactivity: inactivity != 0,
+            activity_source: AxisSet::from_bits(
+                status.act_x_source(),
+                status.act_y_source(),
+                status.act_z_source()
+            ),
+            asleep: status.asleep() != 0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn activity_register_conversion(){
+        let config = ActivityConfig::default()
+            .activity_threshold_g(1.0)
+            .inactivity_threshold_g(0.1875)
+            .activity_axes(AxisSet { x: true, y: false, z: false })
+            .inactivity_axes(AxisSet::NONE)
+            .activity_coupling(Coupling::DC);
+        assert_eq!(config.THRESH_ACT_reg_value(), Some(16));
+        assert_eq!(config.THRESH_INACT_reg_value(), Some(3));
+        assert_eq!(config.ACT_INACT_CTL_reg_value(), 0b0100_1000);
+        assert_eq!(config.activity_threshold_g(20.0).THRESH_ACT_reg_value(), None);
+    }
+
+    #[test]
+    fn activity_status_decoding(){
+        let status = ACT_TAP_STATUS::new().with_act_y_source(1).with_asleep(1);
+        assert_eq!(
+            ActivityStatus::from_registers(1, 0, status),
+            ActivityStatus {
+                activity: true,
+                inactivity: false,
+                activity_source: AxisSet { x: false, y: true, z: false },
+                asleep: true
+            }
+        );
+    }
+}
@@ -0,0 +1,40 @@
+/// A single body frame axis of the adxl343
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+    Z
+}
+
+/// Per-axis flags, used both for enabling an axis in a detection function and for reporting
+/// which axes were involved in an event
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AxisSet {
+    pub x: bool,
+    pub y: bool,
+    pub z: bool
+}
+
+impl AxisSet {
+    pub const ALL: AxisSet = AxisSet { x: true, y: true, z: true };
+    pub const NONE: AxisSet = AxisSet { x: false, y: false, z: false };
+
+    /// builds the set from three register bits (any non-zero value counts as set)
+    pub fn from_bits(x: u8, y: u8, z: u8) -> Self {
+        AxisSet { x: x != 0, y: y != 0, z: z != 0 }
+    }
+
+    /// first axis in the set, checked in the order x, y, z
+    pub fn first(&self) -> Option<Axis> {
+        match (self.x, self.y, self.z) {
+            (true, _, _) => Some(Axis::X),
+            (false, true, _) => Some(Axis::Y),
+            (false, false, true) => Some(Axis::Z),
+            (false, false, false) => None
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !(self.x || self.y || self.z)
+    }
+}
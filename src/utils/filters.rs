@@ -0,0 +1,300 @@
+use core::f32::consts::PI;
+use crate::registers::accel_configs::OutputDataRate;
+use super::settings::ADXL343Settings;
+
+/// Streaming filter over accel readings [x_axis, y_axis, z_axis], run at the ODR of the samples.
+/// Filters are fixed-capacity and chain with then, e.g.
+/// `LowPass::new(20.0, odr).then(MovingAverage::<4>::new(odr))`
+pub trait Filter {
+    /// filters one sample, taken 1/odr after the previous one
+    fn apply(&mut self, sample: [f32; 3]) -> [f32; 3];
+
+    /// forgets previous samples, the next sample is filtered as the first one
+    fn reset(&mut self);
+
+    /// ODR the filter coefficients were computed for
+    fn odr(&self) -> OutputDataRate;
+
+    /// recomputes the filter coefficients for odr and resets the filter
+    fn set_odr(&mut self, odr: OutputDataRate);
+
+    /// true when the coefficients (of every filter in a chain) were computed for odr
+    fn odr_matches(&self, odr: OutputDataRate) -> bool {
+        self.odr() == odr
+    }
+
+    /// filters one sample, first resetting the filter when the ODR in settings has changed
+    fn apply_with_settings(&mut self, settings: &ADXL343Settings, sample: [f32; 3]) -> [f32; 3] {
+        if !self.odr_matches(settings.get_odr()) {
+            self.set_odr(settings.get_odr());
+        }
+        self.apply(sample)
+    }
+
+    /// filters a buffer of consecutive samples (e.g. a FIFO drain) in place, see apply_with_settings
+    fn apply_all(&mut self, settings: &ADXL343Settings, samples: &mut [[f32; 3]]) {
+        for sample in samples {
+            *sample = self.apply_with_settings(settings, *sample);
+        }
+    }
+
+    /// feeds the output of this filter into next
+    fn then<F: Filter>(self, next: F) -> Chain<Self, F>
+    where
+        Self: Sized,
+    {
+        Chain { first: self, second: next }
+    }
+}
+
+/// Two filters run one after the other, see Filter::then
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Chain<A, B> {
+    first: A,
+    second: B
+}
+
+impl<A: Filter, B: Filter> Filter for Chain<A, B> {
+    fn apply(&mut self, sample: [f32; 3]) -> [f32; 3] {
+        self.second.apply(self.first.apply(sample))
+    }
+
+    fn reset(&mut self) {
+        self.first.reset();
+        self.second.reset();
+    }
+
+    /// ODR of the first filter, the second one may differ until set_odr runs, see odr_matches
+    fn odr(&self) -> OutputDataRate {
+        self.first.odr()
+    }
+
+    fn set_odr(&mut self, odr: OutputDataRate) {
+        self.first.set_odr(odr);
+        self.second.set_odr(odr);
+    }
+
+    fn odr_matches(&self, odr: OutputDataRate) -> bool {
+        self.first.odr_matches(odr) && self.second.odr_matches(odr)
+    }
+}
+
+/// time constant RC of a first order filter with the given cutoff, over the sample period
+fn rc_over_dt(cutoff_hz: f32, odr: OutputDataRate) -> f32 {
+    odr.hz() / (2.0 * PI * cutoff_hz)
+}
+
+/// First order (single pole IIR) low-pass filter, for smoothing readings. The first sample after
+/// a reset passes through unchanged
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LowPass {
+    cutoff_hz: f32,
+    odr: OutputDataRate,
+    alpha: f32,
+    output: Option<[f32; 3]>
+}
+
+impl LowPass {
+    /// cutoff_hz must be positive, and is best kept below the bandwidth (ODR / 2)
+    pub fn new(cutoff_hz: f32, odr: OutputDataRate) -> Self {
+        let mut filter = LowPass { cutoff_hz, odr, alpha: 1.0, output: None };
+        filter.set_odr(odr);
+        filter
+    }
+
+    pub fn cutoff_hz(&self) -> f32 {
+        self.cutoff_hz
+    }
+}
+
+impl Filter for LowPass {
+    fn apply(&mut self, sample: [f32; 3]) -> [f32; 3] {
+        let output = match self.output {
+            Some(previous) => core::array::from_fn(|axis| previous[axis] + self.alpha * (sample[axis] - previous[axis])),
+            None => sample
+        };
+        self.output = Some(output);
+        output
+    }
+
+    fn reset(&mut self) {
+        self.output = None;
+    }
+
+    fn odr(&self) -> OutputDataRate {
+        self.odr
+    }
+
+    fn set_odr(&mut self, odr: OutputDataRate) {
+        self.odr = odr;
+        self.alpha = 1.0 / (1.0 + rc_over_dt(self.cutoff_hz, odr));
+        self.reset();
+    }
+}
+
+/// First order high-pass filter, for removing gravity (and offsets) from readings. The output
+/// starts at 0 g after a reset
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HighPass {
+    cutoff_hz: f32,
+    odr: OutputDataRate,
+    alpha: f32,
+    previous: Option<([f32; 3], [f32; 3])>
+}
+
+impl HighPass {
+    /// cutoff_hz must be positive, and is best kept below the bandwidth (ODR / 2)
+    pub fn new(cutoff_hz: f32, odr: OutputDataRate) -> Self {
+        let mut filter = HighPass { cutoff_hz, odr, alpha: 0.0, previous: None };
+        filter.set_odr(odr);
+        filter
+    }
+
+    pub fn cutoff_hz(&self) -> f32 {
+        self.cutoff_hz
+    }
+}
+
+impl Filter for HighPass {
+    fn apply(&mut self, sample: [f32; 3]) -> [f32; 3] {
+        let output = match self.previous {
+            Some((input, output)) => core::array::from_fn(|axis| self.alpha * (output[axis] + sample[axis] - input[axis])),
+            None => [0.0; 3]
+        };
+        self.previous = Some((sample, output));
+        output
+    }
+
+    fn reset(&mut self) {
+        self.previous = None;
+    }
+
+    fn odr(&self) -> OutputDataRate {
+        self.odr
+    }
+
+    fn set_odr(&mut self, odr: OutputDataRate) {
+        let rc_over_dt = rc_over_dt(self.cutoff_hz, odr);
+        self.odr = odr;
+        self.alpha = rc_over_dt / (1.0 + rc_over_dt);
+        self.reset();
+    }
+}
+
+/// Average of the last N samples (or of every sample since the reset, until N have been taken)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MovingAverage<const N: usize> {
+    odr: OutputDataRate,
+    window: [[f32; 3]; N],
+    next: usize,
+    len: usize
+}
+
+impl<const N: usize> MovingAverage<N> {
+    pub fn new(odr: OutputDataRate) -> Self {
+        const { assert!(N > 0, "the moving average window must hold at least one sample") };
+        MovingAverage { odr, window: [[0.0; 3]; N], next: 0, len: 0 }
+    }
+}
+
+impl<const N: usize> Filter for MovingAverage<N> {
+    fn apply(&mut self, sample: [f32; 3]) -> [f32; 3] {
+        self.window[self.next] = sample;
+        self.next = (self.next + 1) % N;
+        self.len = (self.len + 1).min(N);
+        core::array::from_fn(|axis| {
+            self.window[..self.len].iter().map(|sample| sample[axis]).sum::<f32>() / self.len as f32
+        })
+    }
+
+    fn reset(&mut self) {
+        self.next = 0;
+        self.len = 0;
+    }
+
+    fn odr(&self) -> OutputDataRate {
+        self.odr
+    }
+
+    fn set_odr(&mut self, odr: OutputDataRate) {
+        self.odr = odr;
+        self.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(value: [f32; 3], expected: [f32; 3]) {
+        for (axis, expected) in value.iter().zip(expected) {
+            assert!((axis - expected).abs() < 1e-3, "{:?} != {:?}", value, expected);
+        }
+    }
+
+    #[test]
+    fn low_pass(){
+        //RC = 1/(2*pi*fc), with fc = 100/(2*pi) Hz, RC/dt = 1 and alpha = 0.5
+        let mut filter = LowPass::new(100.0 / (2.0 * PI), OutputDataRate::Hz100);
+        assert_close(filter.apply([0.0, 0.0, 1.0]), [0.0, 0.0, 1.0]);
+        assert_close(filter.apply([1.0, 0.0, 1.0]), [0.5, 0.0, 1.0]);
+        assert_close(filter.apply([1.0, 0.0, 1.0]), [0.75, 0.0, 1.0]);
+        for _ in 0..32 {
+            filter.apply([1.0, 0.0, 1.0]);
+        }
+        assert_close(filter.apply([1.0, 0.0, 1.0]), [1.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn high_pass(){
+        let mut filter = HighPass::new(100.0 / (2.0 * PI), OutputDataRate::Hz100);
+        assert_close(filter.apply([0.0, 0.0, 1.0]), [0.0; 3]);
+        assert_close(filter.apply([1.0, 0.0, 1.0]), [0.5, 0.0, 0.0]);
+        assert_close(filter.apply([1.0, 0.0, 1.0]), [0.25, 0.0, 0.0]);
+        //gravity is removed
+        for _ in 0..32 {
+            filter.apply([0.0, 0.0, 1.0]);
+        }
+        assert_close(filter.apply([0.0, 0.0, 1.0]), [0.0; 3]);
+    }
+
+    #[test]
+    fn moving_average(){
+        let mut filter = MovingAverage::<3>::new(OutputDataRate::Hz100);
+        assert_close(filter.apply([3.0, 0.0, 0.0]), [3.0, 0.0, 0.0]);
+        assert_close(filter.apply([0.0, 3.0, 0.0]), [1.5, 1.5, 0.0]);
+        assert_close(filter.apply([0.0, 0.0, 3.0]), [1.0, 1.0, 1.0]);
+        assert_close(filter.apply([0.0, 0.0, 3.0]), [0.0, 1.0, 2.0]);
+        filter.reset();
+        assert_close(filter.apply([0.0, 0.0, 1.0]), [0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn chain_and_odr_change(){
+        let mut settings = ADXL343Settings::default();
+        let mut filter = HighPass::new(1.0, settings.get_odr()).then(MovingAverage::<2>::new(settings.get_odr()));
+        let mut samples = [[0.0, 0.0, 1.0], [0.0, 0.0, 1.0], [0.0, 2.0, 1.0]];
+        filter.apply_all(&settings, &mut samples);
+        assert_close(samples[0], [0.0; 3]);
+        assert_close(samples[1], [0.0; 3]);
+        assert!(samples[2][1] > 0.9);
+
+        //the new ODR resets both filters, so the first output is 0 again
+        settings.set_odr(OutputDataRate::Hz800);
+        assert_close(filter.apply_with_settings(&settings, [0.0, 2.0, 1.0]), [0.0; 3]);
+        assert_eq!(filter.odr(), OutputDataRate::Hz800);
+    }
+
+    #[test]
+    fn chain_of_different_odrs(){
+        let settings = ADXL343Settings::default();
+        let mut filter = MovingAverage::<2>::new(settings.get_odr()).then(LowPass::new(10.0, settings.get_odr()))
+            .then(MovingAverage::<2>::new(OutputDataRate::Hz800));
+        assert!(!filter.odr_matches(settings.get_odr()));
+
+        //the last filter, built at 800 Hz, is resynced (and reset) along with the others
+        filter.apply_with_settings(&settings, [1.0, 0.0, 0.0]);
+        assert!(filter.odr_matches(settings.get_odr()));
+        assert_eq!(filter.second.odr(), OutputDataRate::Hz100);
+    }
+}
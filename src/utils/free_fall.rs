@@ -0,0 +1,106 @@
+#![allow(non_snake_case)]
+use derive_setters::Setters;
+
+use crate::registers::{THRESH_FF_ADDR, TIME_FF_ADDR};
+use super::{interrupts::InterruptSet, to_register_value};
+
+/// THRESH_FF scale factor (62.5 mg/LSB)
+pub const THRESH_FF_G_PER_LSB: f32 = 0.0625;
+/// TIME_FF scale factor (5 ms/LSB)
+pub const TIME_FF_MS_PER_LSB: f32 = 5.0;
+
+/// Datasheet recommended THRESH_FF register values (0x05 - 0x09, i.e. 312.5 mg - 562.5 mg)
+pub const THRESH_FF_RECOMMENDED: (u8, u8) = (0x05, 0x09);
+/// Datasheet recommended TIME_FF register values (0x14 - 0x46, i.e. 100 ms - 350 ms)
+pub const TIME_FF_RECOMMENDED: (u8, u8) = (0x14, 0x46);
+
+/// Free fall detection configuration. The threshold is given in g's and the time in milliseconds, 
+/// both are converted into register values when written to the device.
+/// 
+/// # Fields
+/// 
+/// - `threshold_g` - all axes must read below this acceleration (THRESH_FF)
+/// - `time_ms` - minimum time all axes must stay below threshold_g (TIME_FF)
+/// - `interrupt` - enable the FREE_FALL interrupt
+#[derive(Clone, Copy, Debug, PartialEq, Setters)]
+pub struct FreeFallConfig {
+    pub threshold_g: f32,
+    pub time_ms: f32,
+    pub interrupt: bool
+}
+
+impl Default for FreeFallConfig {
+    fn default() -> Self {
+        FreeFallConfig {
+            threshold_g: 0.4375,
+            time_ms: 150.0,
+            interrupt: true
+        }
+    }
+}
+
+impl FreeFallConfig {
+    /// THRESH_FF register value, None if it falls outside of the recommended 0x05 - 0x09
+    pub fn THRESH_FF_reg_value(&self) -> Option<u8> {
+        let (min, max) = THRESH_FF_RECOMMENDED;
+        to_register_value(self.threshold_g, THRESH_FF_G_PER_LSB)
+            .filter(|value| (min..=max).contains(value))
+    }
+
+    /// TIME_FF register value, None if it falls outside of the recommended 0x14 - 0x46
+    pub fn TIME_FF_reg_value(&self) -> Option<u8> {
+        let (min, max) = TIME_FF_RECOMMENDED;
+        to_register_value(self.time_ms, TIME_FF_MS_PER_LSB)
+            .filter(|value| (min..=max).contains(value))
+    }
+
+    /// decodes the THRESH_FF and TIME_FF register values, taking the interrupt flag from the 
+    /// enabled interrupts
+    pub fn from_registers(thresh_ff: u8, time_ff: u8, enabled: InterruptSet) -> Self {
+        FreeFallConfig {
+            threshold_g: thresh_ff as f32 * THRESH_FF_G_PER_LSB,
+            time_ms: time_ff as f32 * TIME_FF_MS_PER_LSB,
+            interrupt: enabled.contains(InterruptSet::FREE_FALL)
+        }
+    }
+
+    /// (address, value) of both free fall registers, None outside of the recommended values
+    pub(crate) fn register_writes(&self) -> Option<[(u8, u8); 2]> {
+        Some([
+            (THRESH_FF_ADDR, self.THRESH_FF_reg_value()?),
+            (TIME_FF_ADDR, self.TIME_FF_reg_value()?)
+        ])
+    }
+
+    /// the interrupts this configuration enables (FREE_FALL or none)
+    pub fn interrupts(&self) -> InterruptSet {
+        match self.interrupt {
+            true => InterruptSet::FREE_FALL,
+            false => InterruptSet::empty()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn free_fall_register_conversion(){
+        let config = FreeFallConfig::default().threshold_g(0.3125).time_ms(100.0);
+        assert_eq!(config.THRESH_FF_reg_value(), Some(5));
+        assert_eq!(config.TIME_FF_reg_value(), Some(20));
+        assert_eq!(config.threshold_g(0.5625).THRESH_FF_reg_value(), Some(9));
+        assert_eq!(config.time_ms(350.0).TIME_FF_reg_value(), Some(70));
+    }
+
+    #[test]
+    fn free_fall_rejects_out_of_range(){
+        let config = FreeFallConfig::default();
+        assert_eq!(config.threshold_g(0.25).THRESH_FF_reg_value(), None);
+        assert_eq!(config.threshold_g(0.625).THRESH_FF_reg_value(), None);
+        assert_eq!(config.time_ms(50.0).TIME_FF_reg_value(), None);
+        assert_eq!(config.time_ms(400.0).TIME_FF_reg_value(), None);
+        assert_eq!(config.time_ms(f32::NAN).TIME_FF_reg_value(), None);
+    }
+}
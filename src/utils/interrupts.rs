@@ -0,0 +1,205 @@
+use core::ops::{BitAnd, BitOr, BitOrAssign, Not};
+
+use crate::registers::accel_configs::{INT_ENABLE, INT_MAP, INT_SOURCE, ACT_TAP_STATUS};
+use super::{tap::TapEvent, activity::ActivityStatus};
+
+/// Set of interrupt functions, laid out like the INT_ENABLE, INT_MAP and INT_SOURCE registers
+/// (bit 7 = DATA_READY, ..., bit 0 = OVERRUN)
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct InterruptSet(u8);
+
+impl InterruptSet {
+    pub const DATA_READY: InterruptSet = InterruptSet(1 << 7);
+    pub const SINGLE_TAP: InterruptSet = InterruptSet(1 << 6);
+    pub const DOUBLE_TAP: InterruptSet = InterruptSet(1 << 5);
+    pub const ACTIVITY: InterruptSet = InterruptSet(1 << 4);
+    pub const INACTIVITY: InterruptSet = InterruptSet(1 << 3);
+    pub const FREE_FALL: InterruptSet = InterruptSet(1 << 2);
+    pub const WATERMARK: InterruptSet = InterruptSet(1 << 1);
+    pub const OVERRUN: InterruptSet = InterruptSet(1 << 0);
+
+    pub const fn empty() -> Self {
+        InterruptSet(0)
+    }
+
+    pub const fn all() -> Self {
+        InterruptSet(0xFF)
+    }
+
+    pub const fn from_bits(bits: u8) -> Self {
+        InterruptSet(bits)
+    }
+
+    pub const fn bits(&self) -> u8 {
+        self.0
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// true if every function in other is also in self
+    pub const fn contains(&self, other: InterruptSet) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// true if at least one function in other is also in self
+    pub const fn intersects(&self, other: InterruptSet) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    pub fn insert(&mut self, other: InterruptSet) {
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: InterruptSet) {
+        self.0 &= !other.0;
+    }
+
+    /// inserts other when value is true, removes it otherwise
+    pub fn set(&mut self, other: InterruptSet, value: bool) {
+        match value {
+            true => self.insert(other),
+            false => self.remove(other)
+        }
+    }
+}
+
+impl BitOr for InterruptSet {
+    type Output = InterruptSet;
+
+    fn bitor(self, rhs: InterruptSet) -> InterruptSet {
+        InterruptSet(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for InterruptSet {
+    fn bitor_assign(&mut self, rhs: InterruptSet) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for InterruptSet {
+    type Output = InterruptSet;
+
+    fn bitand(self, rhs: InterruptSet) -> InterruptSet {
+        InterruptSet(self.0 & rhs.0)
+    }
+}
+
+impl Not for InterruptSet {
+    type Output = InterruptSet;
+
+    fn not(self) -> InterruptSet {
+        InterruptSet(!self.0)
+    }
+}
+
+impl From<INT_SOURCE> for InterruptSet {
+    fn from(value: INT_SOURCE) -> Self {
+        InterruptSet(value.into_bytes()[0])
+    }
+}
+
+impl From<INT_ENABLE> for InterruptSet {
+    fn from(value: INT_ENABLE) -> Self {
+        InterruptSet(value.into_bytes()[0])
+    }
+}
+
+impl From<INT_MAP> for InterruptSet {
+    fn from(value: INT_MAP) -> Self {
+        InterruptSet(value.into_bytes()[0])
+    }
+}
+
+/// INT_SOURCE and ACT_TAP_STATUS read together (ACT_TAP_STATUS first, as the read of INT_SOURCE 
+/// clears the flags it describes), see ADXL343Interface::read_events
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Events {
+    pub source: InterruptSet,
+    pub act_tap_status: u8
+}
+
+impl Events {
+    /// the flags cleared by a read of INT_SOURCE. DATA_READY, WATERMARK and OVERRUN follow the data 
+    /// registers instead
+    pub const CLEARED_ON_READ: InterruptSet = InterruptSet(
+        InterruptSet::SINGLE_TAP.0 | InterruptSet::DOUBLE_TAP.0 | InterruptSet::ACTIVITY.0 | 
+        InterruptSet::INACTIVITY.0 | InterruptSet::FREE_FALL.0
+    );
+
+    pub fn new(source: InterruptSet, act_tap_status: u8) -> Self {
+        Events { source, act_tap_status }
+    }
+
+    /// tap event (if any) and the first axis involved in it, see TapEvent::from_registers
+    pub fn tap_event(&self) -> Option<TapEvent> {
+        TapEvent::from_registers(
+            self.source.contains(InterruptSet::SINGLE_TAP) as u8,
+            self.source.contains(InterruptSet::DOUBLE_TAP) as u8,
+            ACT_TAP_STATUS::from_bytes([self.act_tap_status])
+        )
+    }
+
+    /// activity/inactivity flags, activity source axes and the asleep bit
+    pub fn activity_status(&self) -> ActivityStatus {
+        ActivityStatus::from_registers(
+            self.source.contains(InterruptSet::ACTIVITY) as u8,
+            self.source.contains(InterruptSet::INACTIVITY) as u8,
+            ACT_TAP_STATUS::from_bytes([self.act_tap_status])
+        )
+    }
+
+    /// true if a free fall event has occurred
+    pub fn free_fall(&self) -> bool {
+        self.source.contains(InterruptSet::FREE_FALL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interrupt_set_matches_register_layout(){
+        let enable = INT_ENABLE::new().with_data_ready(1).with_overrun(1);
+        assert_eq!(InterruptSet::from(enable), InterruptSet::DATA_READY | InterruptSet::OVERRUN);
+
+        let map = INT_MAP::new().with_single_tap(1).with_watermark(1);
+        assert_eq!(InterruptSet::from(map), InterruptSet::SINGLE_TAP | InterruptSet::WATERMARK);
+
+        let source = INT_SOURCE::new()
+            .with_double_tap(1)
+            .with_activity(1)
+            .with_inactivity(1)
+            .with_free_fall(1);
+        assert_eq!(
+            InterruptSet::from(source),
+            InterruptSet::DOUBLE_TAP | InterruptSet::ACTIVITY | InterruptSet::INACTIVITY | InterruptSet::FREE_FALL
+        );
+    }
+
+    #[test]
+    fn interrupt_set_operations(){
+        let mut set = InterruptSet::empty();
+        set.insert(InterruptSet::SINGLE_TAP | InterruptSet::DOUBLE_TAP);
+        assert!(set.contains(InterruptSet::SINGLE_TAP));
+        set.set(InterruptSet::SINGLE_TAP, false);
+        assert!(!set.intersects(InterruptSet::SINGLE_TAP));
+        assert_eq!(set, InterruptSet::DOUBLE_TAP);
+        assert_eq!(!InterruptSet::all(), InterruptSet::empty());
+    }
+
+    #[test]
+    fn decode_events(){
+        use crate::utils::{axes::Axis, tap::TapKind};
+        let events = Events::new(InterruptSet::DOUBLE_TAP | InterruptSet::FREE_FALL | InterruptSet::DATA_READY, 0b0100_1001);
+        assert_eq!(events.tap_event(), Some(TapEvent { kind: TapKind::Double, axis: Some(Axis::Z) }));
+        assert!(events.free_fall());
+        let activity = events.activity_status();
+        assert!(!activity.activity && !activity.inactivity);
+        assert!(activity.asleep);
+        assert_eq!(activity.activity_source.first(), Some(Axis::X));
+    }
+}
@@ -1 +1,34 @@
-pub mod settings;
\ No newline at end of file
+pub mod settings;
+pub mod axes;
+pub mod tap;
+pub mod activity;
+pub mod free_fall;
+pub mod interrupts;
+pub mod offsets;
+pub mod self_test;
+pub mod orientation;
+pub mod filters;
+pub mod validation;
+
+/// converts a non-negative quantity into an unsigned register value with the given scale factor,
+/// rounding to the nearest lsb. Returns None if the result does not fit in a register
+pub(crate) fn to_register_value(value: f32, units_per_lsb: f32) -> Option<u8> {
+    let lsbs = value / units_per_lsb;
+    if !(0.0..(u8::MAX as f32 + 0.5)).contains(&lsbs) {
+        return None;
+    }
+    Some((lsbs + 0.5) as u8)
+}
+
+/// converts a quantity into a two's complement register value with the given scale factor,
+/// rounding to the nearest lsb (ties away from zero). Returns None if the result does not fit in a register
+pub(crate) fn to_signed_register_value(value: f32, units_per_lsb: f32) -> Option<i8> {
+    let lsbs = value / units_per_lsb;
+    if !((i8::MIN as f32 - 0.5)..(i8::MAX as f32 + 0.5)).contains(&lsbs) {
+        return None;
+    }
+    match lsbs < 0.0 {
+        true => Some((lsbs - 0.5) as i8),
+        false => Some((lsbs + 0.5) as i8)
+    }
+}
@@ -0,0 +1,60 @@
+use super::to_signed_register_value;
+
+/// OFSX, OFSY and OFSZ scale factor (15.6 mg/LSB, two's complement)
+pub const OFS_G_PER_LSB: f32 = 0.015625;
+
+/// Orientation the device is held in during offset calibration, named after the axis pointing
+/// up (away from the earth), which reads +1 g
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CalibrationOrientation {
+    XUp,
+    XDown,
+    YUp,
+    YDown,
+    #[default]
+    ZUp,
+    ZDown
+}
+
+impl CalibrationOrientation {
+    /// the reading [x_axis, y_axis, z_axis] (in g's) an ideal device returns in this orientation
+    pub fn expected_accel(&self) -> [f32; 3] {
+        match self {
+            CalibrationOrientation::XUp => [1.0, 0.0, 0.0],
+            CalibrationOrientation::XDown => [-1.0, 0.0, 0.0],
+            CalibrationOrientation::YUp => [0.0, 1.0, 0.0],
+            CalibrationOrientation::YDown => [0.0, -1.0, 0.0],
+            CalibrationOrientation::ZUp => [0.0, 0.0, 1.0],
+            CalibrationOrientation::ZDown => [0.0, 0.0, -1.0]
+        }
+    }
+}
+
+/// converts per-axis offsets in g's into OFSX, OFSY and OFSZ register values, None if an offset 
+/// falls outside of -2 g - 1.98 g
+pub fn offsets_to_reg_values(offsets_g: [f32; 3]) -> Option<[i8; 3]> {
+    Some([
+        to_signed_register_value(offsets_g[0], OFS_G_PER_LSB)?,
+        to_signed_register_value(offsets_g[1], OFS_G_PER_LSB)?,
+        to_signed_register_value(offsets_g[2], OFS_G_PER_LSB)?
+    ])
+}
+
+/// converts OFSX, OFSY and OFSZ register values into per-axis offsets in g's
+pub fn reg_values_to_offsets(reg_values: [i8; 3]) -> [f32; 3] {
+    reg_values.map(|value| value as f32 * OFS_G_PER_LSB)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offset_conversion(){
+        assert_eq!(offsets_to_reg_values([0.0, -0.5, 0.0625]), Some([0, -32, 4]));
+        assert_eq!(offsets_to_reg_values([-2.0, 1.984375, -0.0234375]), Some([-128, 127, -2]));
+        assert_eq!(offsets_to_reg_values([2.0, 0.0, 0.0]), None);
+        assert_eq!(offsets_to_reg_values([0.0, -2.1, 0.0]), None);
+        assert_eq!(reg_values_to_offsets([-128, 127, 4]), [-2.0, 1.984375, 0.0625]);
+    }
+}
@@ -0,0 +1,222 @@
+use core::f32::consts::PI;
+use libm::{acosf, atan2f, sqrtf};
+
+const DEG_PER_RAD: f32 = 180.0 / PI;
+
+/// Angles of the device relative to gravity, in degrees, computed from a (live, buffered or
+/// filtered) accel reading [x_axis, y_axis, z_axis] taken while the device is not accelerating
+///
+/// # Fields
+///
+/// - `pitch_deg` - rotation about the y axis, positive when the +x axis points down (-90 to 90)
+/// - `roll_deg` - rotation about the x axis, positive when the +y axis points up (-180 to 180)
+/// - `tilt_deg` - angle between the +z axis and vertical, 0 when lying flat face up (0 to 180)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tilt {
+    pub pitch_deg: f32,
+    pub roll_deg: f32,
+    pub tilt_deg: f32
+}
+
+impl Tilt {
+    /// None when the reading has no magnitude (e.g. in free fall)
+    pub fn from_accel(accel_g: [f32; 3]) -> Option<Self> {
+        let [x, y, z] = accel_g;
+        let magnitude = magnitude(accel_g);
+        if magnitude == 0.0 {
+            return None;
+        }
+        Some(Tilt {
+            pitch_deg: atan2f(-x, sqrtf(y * y + z * z)) * DEG_PER_RAD,
+            roll_deg: atan2f(y, z) * DEG_PER_RAD,
+            tilt_deg: acosf((z / magnitude).clamp(-1.0, 1.0)) * DEG_PER_RAD
+        })
+    }
+}
+
+/// Side of the device facing up, named after the axis pointing up (which reads +1 g)
+///
+/// # Variants
+///
+/// - `FaceUp` / `FaceDown` - +z / -z axis up
+/// - `PortraitUp` / `PortraitDown` - +y / -y axis up
+/// - `LandscapeRight` / `LandscapeLeft` - +x / -x axis up
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Face {
+    FaceUp,
+    FaceDown,
+    PortraitUp,
+    PortraitDown,
+    LandscapeRight,
+    LandscapeLeft
+}
+
+impl Face {
+    pub const ALL: [Face; 6] = [
+        Face::FaceUp, Face::FaceDown, Face::PortraitUp, Face::PortraitDown, Face::LandscapeRight, Face::LandscapeLeft
+    ];
+
+    /// unit vector of the axis pointing up
+    pub fn up_vector(&self) -> [f32; 3] {
+        match self {
+            Face::FaceUp => [0.0, 0.0, 1.0],
+            Face::FaceDown => [0.0, 0.0, -1.0],
+            Face::PortraitUp => [0.0, 1.0, 0.0],
+            Face::PortraitDown => [0.0, -1.0, 0.0],
+            Face::LandscapeRight => [1.0, 0.0, 0.0],
+            Face::LandscapeLeft => [-1.0, 0.0, 0.0]
+        }
+    }
+
+    /// angle in degrees between the reading and up_vector, None when the reading has no magnitude
+    pub fn angle_deg(&self, accel_g: [f32; 3]) -> Option<f32> {
+        let magnitude = magnitude(accel_g);
+        if magnitude == 0.0 {
+            return None;
+        }
+        let up = self.up_vector();
+        let dot = accel_g[0] * up[0] + accel_g[1] * up[1] + accel_g[2] * up[2];
+        Some(acosf((dot / magnitude).clamp(-1.0, 1.0)) * DEG_PER_RAD)
+    }
+
+    /// face whose up_vector is closest to the reading, None when the reading has no magnitude
+    pub fn nearest(accel_g: [f32; 3]) -> Option<Face> {
+        let mut nearest: Option<(Face, f32)> = None;
+        for face in Face::ALL {
+            let angle = face.angle_deg(accel_g)?;
+            if nearest.is_none_or(|(_, best)| angle < best) {
+                nearest = Some((face, angle));
+            }
+        }
+        nearest.map(|(face, _)| face)
+    }
+}
+
+/// Six-face orientation classifier. The reported face only changes once another face is closer to
+/// the reading by more than hysteresis_deg, so readings near the boundary between two faces
+/// (45 degrees from both) do not make the output toggle
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FaceClassifier {
+    hysteresis_deg: f32,
+    current: Option<Face>
+}
+
+impl Default for FaceClassifier {
+    fn default() -> Self {
+        FaceClassifier::new(10.0)
+    }
+}
+
+impl FaceClassifier {
+    pub fn new(hysteresis_deg: f32) -> Self {
+        FaceClassifier { hysteresis_deg, current: None }
+    }
+
+    pub fn hysteresis_deg(&self) -> f32 {
+        self.hysteresis_deg
+    }
+
+    /// classifies a reading, returning the current face. Readings without magnitude leave it unchanged
+    pub fn update(&mut self, accel_g: [f32; 3]) -> Option<Face> {
+        let Some(nearest) = Face::nearest(accel_g) else {
+            return self.current;
+        };
+        self.current = match self.current {
+            Some(current) => {
+                let current_angle = current.angle_deg(accel_g)?;
+                let nearest_angle = nearest.angle_deg(accel_g)?;
+                match current_angle - nearest_angle > self.hysteresis_deg {
+                    true => Some(nearest),
+                    false => Some(current)
+                }
+            },
+            None => Some(nearest)
+        };
+        self.current
+    }
+
+    /// last classified face, None before the first reading with magnitude
+    pub fn current(&self) -> Option<Face> {
+        self.current
+    }
+
+    /// forgets the current face, the next reading is classified without hysteresis
+    pub fn reset(&mut self) {
+        self.current = None;
+    }
+}
+
+fn magnitude(accel_g: [f32; 3]) -> f32 {
+    sqrtf(accel_g[0] * accel_g[0] + accel_g[1] * accel_g[1] + accel_g[2] * accel_g[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(value: f32, expected: f32) {
+        assert!((value - expected).abs() < 1e-3, "{} != {}", value, expected);
+    }
+
+    #[test]
+    fn tilt_angles(){
+        let flat = Tilt::from_accel([0.0, 0.0, 1.0]).unwrap();
+        assert_close(flat.pitch_deg, 0.0);
+        assert_close(flat.roll_deg, 0.0);
+        assert_close(flat.tilt_deg, 0.0);
+
+        let x_down = Tilt::from_accel([-1.0, 0.0, 0.0]).unwrap();
+        assert_close(x_down.pitch_deg, 90.0);
+        assert_close(x_down.tilt_deg, 90.0);
+
+        let y_up = Tilt::from_accel([0.0, 1.0, 0.0]).unwrap();
+        assert_close(y_up.roll_deg, 90.0);
+
+        let upside_down = Tilt::from_accel([0.0, 0.0, -1.0]).unwrap();
+        assert_close(upside_down.tilt_deg, 180.0);
+
+        //magnitude does not matter
+        let half = Tilt::from_accel([0.5, 0.0, 0.5]).unwrap();
+        assert_close(half.pitch_deg, -45.0);
+        assert_close(half.tilt_deg, 45.0);
+
+        assert_eq!(Tilt::from_accel([0.0; 3]), None);
+    }
+
+    #[test]
+    fn nearest_face(){
+        assert_eq!(Face::nearest([0.1, -0.2, 0.95]), Some(Face::FaceUp));
+        assert_eq!(Face::nearest([0.0, 0.0, -1.0]), Some(Face::FaceDown));
+        assert_eq!(Face::nearest([0.2, 0.9, 0.1]), Some(Face::PortraitUp));
+        assert_eq!(Face::nearest([0.0, -1.0, 0.0]), Some(Face::PortraitDown));
+        assert_eq!(Face::nearest([1.0, 0.0, 0.3]), Some(Face::LandscapeRight));
+        assert_eq!(Face::nearest([-1.0, 0.0, 0.0]), Some(Face::LandscapeLeft));
+        assert_eq!(Face::nearest([0.0; 3]), None);
+    }
+
+    #[test]
+    fn classifier_hysteresis(){
+        let mut classifier = FaceClassifier::new(10.0);
+        assert_eq!(classifier.update([0.0; 3]), None);
+        assert_eq!(classifier.update([0.0, 0.0, 1.0]), Some(Face::FaceUp));
+
+        //just past 45 degrees towards +y: both faces are about as close, no change
+        let angle = 48.0 / DEG_PER_RAD;
+        let reading = [0.0, libm::sinf(angle), libm::cosf(angle)];
+        assert_eq!(classifier.update(reading), Some(Face::FaceUp));
+
+        //60 degrees: PortraitUp is 20 degrees closer
+        let angle = 60.0 / DEG_PER_RAD;
+        let reading = [0.0, libm::sinf(angle), libm::cosf(angle)];
+        assert_eq!(classifier.update(reading), Some(Face::PortraitUp));
+
+        //back to 42 degrees, FaceUp is closer but not by more than the hysteresis
+        let angle = 42.0 / DEG_PER_RAD;
+        let reading = [0.0, libm::sinf(angle), libm::cosf(angle)];
+        assert_eq!(classifier.update(reading), Some(Face::PortraitUp));
+        assert_eq!(classifier.update([0.0; 3]), Some(Face::PortraitUp));
+
+        classifier.reset();
+        assert_eq!(classifier.update(reading), Some(Face::FaceUp));
+    }
+}
@@ -0,0 +1,84 @@
+/// Self-test output change limits [x_axis, y_axis, z_axis] (min, max) in g's, at a 2.5 V supply
+pub const SELF_TEST_LIMITS_G: [(f32, f32); 3] = [(0.20, 2.10), (-2.10, -0.20), (0.30, 3.40)];
+
+/// Supply voltages the self-test scale factors are specified at, with the factors for the 
+/// x and y axes and for the z axis (datasheet self-test scale factor table)
+const SELF_TEST_SCALE_FACTORS: [(f32, f32, f32); 5] = [
+    (2.00, 0.64, 0.80),
+    (2.50, 1.00, 1.00),
+    (3.00, 1.77, 1.47),
+    (3.30, 2.11, 1.69),
+    (3.60, 2.61, 1.95),
+];
+
+/// Self-test scale factors (x and y axes, z axis) for a supply voltage, linearly interpolated 
+/// between the datasheet values. None outside of the 2.0 V - 3.6 V supply range
+pub fn self_test_scale_factors(supply_voltage: f32) -> Option<(f32, f32)> {
+    SELF_TEST_SCALE_FACTORS.windows(2).find_map(|pair| {
+        let (v_low, xy_low, z_low) = pair[0];
+        let (v_high, xy_high, z_high) = pair[1];
+        if !(v_low..=v_high).contains(&supply_voltage) {
+            return None;
+        }
+        let t = (supply_voltage - v_low) / (v_high - v_low);
+        Some((xy_low + t * (xy_high - xy_low), z_low + t * (z_high - z_low)))
+    })
+}
+
+/// Result of the built-in self-test: the per-axis output change (self-test on - self-test off)
+/// compared to the datasheet limits scaled for the supply voltage
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SelfTestReport {
+    pub deltas_g: [f32; 3],
+    pub limits_g: [(f32, f32); 3],
+    pub passed: [bool; 3]
+}
+
+impl SelfTestReport {
+    /// Compares deltas_g [x_axis, y_axis, z_axis] to the limits at supply_voltage. None outside of 
+    /// the 2.0 V - 3.6 V supply range
+    pub fn new(deltas_g: [f32; 3], supply_voltage: f32) -> Option<Self> {
+        let (xy_factor, z_factor) = self_test_scale_factors(supply_voltage)?;
+        let factors = [xy_factor, xy_factor, z_factor];
+        let limits_g: [(f32, f32); 3] = core::array::from_fn(|axis| {
+            let (min, max) = SELF_TEST_LIMITS_G[axis];
+            (min * factors[axis], max * factors[axis])
+        });
+        let passed = core::array::from_fn(|axis| (limits_g[axis].0..=limits_g[axis].1).contains(&deltas_g[axis]));
+        Some(SelfTestReport { deltas_g, limits_g, passed })
+    }
+
+    /// true if every axis is within its limits
+    pub fn passed_all(&self) -> bool {
+        self.passed.iter().all(|&passed| passed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scale_factor_interpolation(){
+        assert_eq!(self_test_scale_factors(2.5), Some((1.0, 1.0)));
+        assert_eq!(self_test_scale_factors(3.3), Some((2.11, 1.69)));
+        let (xy, z) = self_test_scale_factors(2.75).unwrap();
+        assert!((xy - 1.385).abs() < 1e-5 && (z - 1.235).abs() < 1e-5);
+        assert_eq!(self_test_scale_factors(1.8), None);
+        assert_eq!(self_test_scale_factors(3.7), None);
+    }
+
+    #[test]
+    fn report_pass_fail(){
+        let report = SelfTestReport::new([0.5, -0.5, 1.0], 2.5).unwrap();
+        assert!(report.passed_all());
+
+        let report = SelfTestReport::new([0.1, -0.5, 3.5], 2.5).unwrap();
+        assert_eq!(report.passed, [false, true, false]);
+        assert!(!report.passed_all());
+
+        //limits grow with the supply voltage
+        let report = SelfTestReport::new([0.1, -0.5, 3.5], 3.3).unwrap();
+        assert_eq!(report.passed, [false, true, true]);
+    }
+}
@@ -1,30 +1,104 @@
 #![allow(unused, non_snake_case)]
 use derive_setters::Setters;
 
+use super::{tap::TapConfig, activity::ActivityConfig, free_fall::FreeFallConfig, interrupts::InterruptSet, validation::ValidationReport};
 use crate::registers::{
-    BW_RATE_ADDR,
+    BW_RATE_ADDR, DATA_FORMAT_ADDR, FIFO_CTL_ADDR, INT_MAP_ADDR, INT_ENABLE_ADDR, FIFO_SAMPLES_MAX,
+    OFSX_ADDR, OFSY_ADDR, OFSZ_ADDR, POWER_CTL_ADDR, THRESH_TAP_ADDR, DUR_ADDR, LATENT_ADDR, WINDOW_ADDR, TAP_AXES_ADDR,
+    THRESH_ACT_ADDR, THRESH_INACT_ADDR, TIME_INACT_ADDR, ACT_INACT_CTL_ADDR, THRESH_FF_ADDR, TIME_FF_ADDR,
+    RegisterWrites,
     accel_configs::*
 };
+use crate::adxl343_interface::ADXL343Error;
 
 
-#[derive(Default, Clone, Copy)]
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
 pub struct ADXL343Settings{
     odr: OutputDataRate ,
     range: AccelRange,
     justification: Alignment,
     resolution: FullRes,
     low_power_mode: bool,
-    measurement_mode: bool //value of zero indicates off
+    measurement_mode: bool, //value of zero indicates off
+    fifo_mode: FIFOMode,
+    fifo_samples: u8,
+    fifo_trigger: InterruptPin,
+    tap: Option<TapConfig>,
+    activity: Option<ActivityConfig>,
+    free_fall: Option<FreeFallConfig>,
+    interrupt_enable: InterruptSet,
+    interrupt_map: InterruptSet, //functions in the set are routed to INT2, the rest to INT1
+    spi_3wire: bool,
+    offsets: [i8; 3], //OFSX, OFSY, OFSZ register values (15.6 mg/LSB)
+    self_test: bool,
+    wakeup_rate: SLEEP_MODE_ODR,
+    sleep: bool,
+    autosleep: bool,
+    link: bool
 }
 
 impl ADXL343Settings {
 
+    /// Decodes the settings the device is configured with. read returns the value of the register at
+    /// the given address, and is called for BW_RATE, DATA_FORMAT, POWER_CTL, FIFO_CTL, INT_ENABLE,
+    /// INT_MAP and OFSX - OFSZ, plus the tap, activity and free fall registers of every function 
+    /// with an enabled interrupt (the configs of the others stay None)
+    pub fn from_register_values(mut read: impl FnMut(u8) -> u8) -> Self {
+        let bw_rate = BW_RATE::from_bytes([read(BW_RATE_ADDR)]);
+        let data_format = DATA_FORMAT::from_bytes([read(DATA_FORMAT_ADDR)]);
+        let power_ctl = POWER_CTL::from_bytes([read(POWER_CTL_ADDR)]);
+        let fifo_ctl = FIFO_CTL::from_bytes([read(FIFO_CTL_ADDR)]);
+        let enabled = InterruptSet::from_bits(read(INT_ENABLE_ADDR));
+
+        let mut settings = ADXL343Settings{
+            odr: bw_rate.odr(),
+            range: data_format.range(),
+            justification: data_format.justisfy(),
+            resolution: data_format.full_res(),
+            low_power_mode: bw_rate.low_power() != 0,
+            measurement_mode: power_ctl.measure() != 0,
+            fifo_mode: fifo_ctl.fifo_mode(),
+            fifo_samples: fifo_ctl.samples(),
+            fifo_trigger: fifo_ctl.trigger(),
+            interrupt_enable: enabled,
+            interrupt_map: InterruptSet::from_bits(read(INT_MAP_ADDR)),
+            spi_3wire: data_format.spi_mode() != 0,
+            offsets: [read(OFSX_ADDR) as i8, read(OFSY_ADDR) as i8, read(OFSZ_ADDR) as i8],
+            self_test: data_format.self_test() != 0,
+            wakeup_rate: power_ctl.wakeup(),
+            sleep: power_ctl.sleep() != 0,
+            autosleep: power_ctl.autosleep() != 0,
+            link: power_ctl.link() != 0,
+            ..Default::default()
+        };
+        if enabled.intersects(InterruptSet::SINGLE_TAP | InterruptSet::DOUBLE_TAP) {
+            settings.tap = Some(TapConfig::from_registers(
+                read(THRESH_TAP_ADDR), read(DUR_ADDR), read(LATENT_ADDR), read(WINDOW_ADDR),
+                TAP_AXES::from_bytes([read(TAP_AXES_ADDR)]), enabled
+            ));
+        }
+        if enabled.intersects(InterruptSet::ACTIVITY | InterruptSet::INACTIVITY) {
+            settings.activity = Some(ActivityConfig::from_registers(
+                read(THRESH_ACT_ADDR), read(THRESH_INACT_ADDR), read(TIME_INACT_ADDR),
+                ACT_INACT_CTL::from_bytes([read(ACT_INACT_CTL_ADDR)]), enabled
+            ));
+        }
+        if enabled.contains(InterruptSet::FREE_FALL) {
+            settings.free_fall = Some(FreeFallConfig::from_registers(
+                read(THRESH_FF_ADDR), read(TIME_FF_ADDR), enabled
+            ));
+        }
+        settings
+    }
+
     ///returns the configured state of the DATA_FORMAT reg IN STRUCT
     pub fn DATA_FORMAT_reg_value(&self) -> u8 {
         DATA_FORMAT::new()
             .with_range(self.range)
             .with_justisfy(self.justification)
-            .with_full_res(self.resolution).into_bytes()[0]
+            .with_full_res(self.resolution)
+            .with_spi_mode(self.spi_3wire as u8)
+            .with_self_test(self.self_test as u8).into_bytes()[0]
     }
 
     ///returns the configured state of the BW_RATE reg IN STRUCT 
@@ -33,7 +107,135 @@ impl ADXL343Settings {
         .with_low_power(self.low_power_mode as u8)
         .with_odr(self.odr).into_bytes()[0]
     }
+
+    ///returns the configured state of the POWER_CTL reg IN STRUCT (every field, including measure)
+    pub fn POWER_CTL_reg_value(&self) -> u8{
+        POWER_CTL::new()
+        .with_wakeup(self.wakeup_rate)
+        .with_sleep(self.sleep as u8)
+        .with_measure(self.measurement_mode as u8)
+        .with_autosleep(self.autosleep as u8)
+        .with_link(self.link as u8).into_bytes()[0]
+    }
+
+    ///returns the configured state of the FIFO_CTL reg IN STRUCT
+    ///(the samples field is masked to 5 bits, check fifo_samples_in_range first)
+    pub fn FIFO_CTL_reg_value(&self) -> u8{
+        FIFO_CTL::new()
+        .with_samples(self.fifo_samples & FIFO_SAMPLES_MAX)
+        .with_trigger(self.fifo_trigger)
+        .with_fifo_mode(self.fifo_mode).into_bytes()[0]
+    }
+    
+    ///returns the configured state of the INT_ENABLE reg IN STRUCT
+    pub fn INT_ENABLE_reg_value(&self) -> u8{
+        self.interrupt_enable.bits()
+    }
+
+    ///returns the configured state of the INT_MAP reg IN STRUCT
+    pub fn INT_MAP_reg_value(&self) -> u8{
+        self.interrupt_map.bits()
+    }
     
+    /// Register writes performed by init, in order: BW_RATE, DATA_FORMAT, FIFO_CTL, OFSX - OFSZ, the tap, activity
+    /// and free fall registers (when configured), then INT_MAP and INT_ENABLE, so no interrupt is 
+    /// enabled before its function is configured. None if a configured value does not fit in its register
+    pub(crate) fn init_register_writes(&self) -> Option<RegisterWrites> {
+        if !self.fifo_samples_in_range() {
+            return None;
+        }
+        let mut writes = RegisterWrites::new();
+        writes.push(BW_RATE_ADDR, self.BW_RATE_reg_value());
+        writes.push(DATA_FORMAT_ADDR, self.DATA_FORMAT_reg_value());
+        writes.push(FIFO_CTL_ADDR, self.FIFO_CTL_reg_value());
+        writes.push(OFSX_ADDR, self.offsets[0] as u8);
+        writes.push(OFSY_ADDR, self.offsets[1] as u8);
+        writes.push(OFSZ_ADDR, self.offsets[2] as u8);
+        if let Some(tap) = self.tap {
+            writes.extend(&tap.register_writes()?);
+        }
+        if let Some(activity) = self.activity {
+            writes.extend(&activity.register_writes()?);
+        }
+        if let Some(free_fall) = self.free_fall {
+            writes.extend(&free_fall.register_writes()?);
+        }
+        writes.push(INT_MAP_ADDR, self.INT_MAP_reg_value());
+        writes.push(INT_ENABLE_ADDR, self.INT_ENABLE_reg_value());
+        Some(writes)
+    }
+
+    /// Checks settings handed to a driver's with_settings, returning them with the SPI bit of 
+    /// DATA_FORMAT following the bus (false over I2c). Returns MeasurementModeBeforeConfig when the 
+    /// settings are in measurement mode, which must only be entered once the device is configured
+    pub(crate) fn checked_for_bus<E>(mut self, three_wire_spi: bool) -> Result<Self, ADXL343Error<E>> {
+        if self.in_measurement_mode() {
+            return Err(ADXL343Error::MeasurementModeBeforeConfig);
+        }
+        self.set_spi_3wire(three_wire_spi);
+        Ok(self)
+    }
+
+    /// init_register_writes for a driver's init. Returns LowPowerOdrUnsupported when low power mode 
+    /// is enabled at an ODR outside of 12.5 Hz - 400 Hz, and ValueOutOfRange when a configured 
+    /// value does not fit in its register
+    pub(crate) fn checked_init_writes<E>(&self) -> Result<RegisterWrites, ADXL343Error<E>> {
+        if !self.low_power_odr_supported() {
+            return Err(ADXL343Error::LowPowerOdrUnsupported);
+        }
+        self.init_register_writes().ok_or(ADXL343Error::ValueOutOfRange)
+    }
+
+    /// converts accel_data (represented as [DATA_0, DATA_1]) into the axis reading in lsb's, for the 
+    /// range, resolution and justification of these settings. Right justified readings are sign 
+    /// extended by the device, left justified readings hold the sign in bit 7 of DATA_1 and are 
+    /// shifted down (arithmetically) by 16 - resolution_to_bits. Does not touch the device, so it can 
+    /// also decode buffered or recorded samples
+    pub fn axis_value_raw(&self, accel_data: [u8; 2]) -> i16 {
+        let axis_value = i16::from_le_bytes(accel_data);
+        match self.justification {
+            Alignment::right => axis_value,
+            Alignment::left => axis_value >> (16 - self.resolution_to_bits())
+        }
+    }
+
+    /// converts a full sample [x_low, x_high, y_low, y_high, z_low, z_high] into the reading of 
+    /// each axis in lsb's [x_axis, y_axis, z_axis], see axis_value_raw
+    pub fn sample_to_raw_axes(&self, sample: [u8; 6]) -> [i16; 3] {
+        let (axis_samples, _) = sample.as_chunks::<2>();
+        [
+            self.axis_value_raw(axis_samples[0]),
+            self.axis_value_raw(axis_samples[1]),
+            self.axis_value_raw(axis_samples[2])
+        ]
+    }
+
+    /// converts the reading of each axis in lsb's into g's
+    pub fn raw_axes_to_g(&self, raw: [i16; 3]) -> [f32; 3] {
+        raw.map(|axis| axis as f32 * self.g_per_lsb())
+    }
+
+    /// converts the reading of each axis in lsb's into milli-g's with integer math, rounding to the 
+    /// nearest milli-g (ties away from zero). Equal to rounding raw_axes_to_g * 1000 the same way
+    pub fn raw_axes_to_mg(&self, raw: [i16; 3]) -> [i32; 3] {
+        let lsb_per_g = self.lsb_per_g() as i32;
+        raw.map(|axis| {
+            let numerator = 2 * 1000 * axis as i32;
+            match numerator < 0 {
+                true => (numerator - lsb_per_g) / (2 * lsb_per_g),
+                false => (numerator + lsb_per_g) / (2 * lsb_per_g)
+            }
+        })
+    }
+
+    /// converts the reading of each axis in lsb's into g's as I16F16 fixed point numbers (requires 
+    /// the `fixed` feature). lsb_per_g is a power of two, so the conversion is exact
+    #[cfg(feature = "fixed")]
+    pub fn raw_axes_to_fixed(&self, raw: [i16; 3]) -> [fixed::types::I16F16; 3] {
+        let shift = 16 - self.lsb_per_g().trailing_zeros();
+        raw.map(|axis| fixed::types::I16F16::from_bits((axis as i32) << shift))
+    }
+
     ///returns the number of bits used to represent axis reading
     pub fn resolution_to_bits(&self) -> u8 {
         match (self.resolution, self.range) {
@@ -95,11 +297,347 @@ impl ADXL343Settings {
 	self.range = range;
     }
 
+    pub fn get_range(&self) -> AccelRange{
+        self.range
+    }
+
+    pub fn set_resolution(&mut self, resolution: FullRes){
+        self.resolution = resolution;
+    }
+
+    pub fn get_resolution(&self) -> FullRes{
+        self.resolution
+    }
+
+    /// reduced power operation (LOW_POWER bit of BW_RATE), only effective from 12.5 Hz to 400 Hz. 
+    /// init rejects any other ODR while enabled, see low_power_odr_supported
+    pub fn set_low_power_mode(&mut self, low_power_mode: bool){
+        self.low_power_mode = low_power_mode;
+    }
+
+    pub fn get_low_power_mode(&self) -> bool{
+        self.low_power_mode
+    }
+
+    /// false when low power mode is enabled at an ODR the datasheet gives no power benefit for 
+    /// (below 12.5 Hz) or lists increased noise for (above 400 Hz)
+    pub fn low_power_odr_supported(&self) -> bool{
+        !self.low_power_mode || self.odr.supports_low_power()
+    }
+
+    /// typical supply current in µA (VS = 2.5 V) for the configured ODR and power mode, see 
+    /// OutputDataRate::normal_current_ua and OutputDataRate::low_power_current_ua. Low power mode 
+    /// outside of 12.5 Hz - 400 Hz draws normal mode current
+    pub fn supply_current_ua(&self) -> u16{
+        match self.low_power_mode {
+            true => self.odr.low_power_current_ua().unwrap_or(self.odr.normal_current_ua()),
+            false => self.odr.normal_current_ua()
+        }
+    }
+
+    /// checks the configuration for an I2c bus running at bus_speed_hz, returning the errors (e.g. an 
+    /// ODR the bus cannot keep up with in bypass or stream mode) and warnings found, see ConfigIssue
+    pub fn validate(&self, bus_speed_hz: u32) -> ValidationReport{
+        ValidationReport::new(self, bus_speed_hz)
+    }
+
+    /// frequency of readings in sleep mode (wakeup bits of POWER_CTL)
+    pub fn set_wakeup_rate(&mut self, wakeup_rate: SLEEP_MODE_ODR){
+        self.wakeup_rate = wakeup_rate;
+    }
+
+    pub fn get_wakeup_rate(&self) -> SLEEP_MODE_ODR{
+        self.wakeup_rate
+    }
+
+    /// sleep bit of POWER_CTL
+    pub fn set_sleep(&mut self, sleep: bool){
+        self.sleep = sleep;
+    }
+
+    pub fn in_sleep_mode(&self) -> bool{
+        self.sleep
+    }
+
+    /// AUTO_SLEEP bit of POWER_CTL, only effective with link set
+    pub fn set_autosleep(&mut self, autosleep: bool){
+        self.autosleep = autosleep;
+    }
+
+    pub fn get_autosleep(&self) -> bool{
+        self.autosleep
+    }
+
+    /// link bit of POWER_CTL
+    pub fn set_link(&mut self, link: bool){
+        self.link = link;
+    }
+
+    pub fn get_link(&self) -> bool{
+        self.link
+    }
+
+    /// self-test force (SELF_TEST bit of DATA_FORMAT), see ADXL343Interface::run_self_test
+    pub fn set_self_test(&mut self, self_test: bool){
+        self.self_test = self_test;
+    }
+
+    pub fn get_self_test(&self) -> bool{
+        self.self_test
+    }
+
+    /// selects 3-wire SPI mode (SPI bit of DATA_FORMAT), set by the driver to match its bus
+    pub fn set_spi_3wire(&mut self, spi_3wire: bool){
+        self.spi_3wire = spi_3wire;
+    }
+
+    pub fn get_spi_3wire(&self) -> bool{
+        self.spi_3wire
+    }
+
+    /// OFSX, OFSY and OFSZ register values (15.6 mg/LSB), see utils::offsets for conversions from g's
+    pub fn set_offsets(&mut self, offsets: [i8; 3]){
+        self.offsets = offsets;
+    }
+
+    pub fn get_offsets(&self) -> [i8; 3]{
+        self.offsets
+    }
+
+    pub fn set_fifo_mode(&mut self, fifo_mode: FIFOMode){
+        self.fifo_mode = fifo_mode;
+    }
+
+    pub fn get_fifo_mode(&self) -> FIFOMode{
+        self.fifo_mode
+    }
+
+    /// watermark in FIFO mode, number of samples kept before the trigger event in trigger mode
+    pub fn set_fifo_samples(&mut self, samples: u8){
+        self.fifo_samples = samples;
+    }
+
+    pub fn get_fifo_samples(&self) -> u8{
+        self.fifo_samples
+    }
+
+    /// false when the samples field will not fit in FIFO_CTL (0 - 31)
+    pub fn fifo_samples_in_range(&self) -> bool{
+        self.fifo_samples <= FIFO_SAMPLES_MAX
+    }
+
+    /// interrupt line the trigger event is linked to (trigger mode only)
+    pub fn set_fifo_trigger(&mut self, trigger: InterruptPin){
+        self.fifo_trigger = trigger;
+    }
+
+    pub fn get_fifo_trigger(&self) -> InterruptPin{
+        self.fifo_trigger
+    }
+
+    /// tap detection configuration written by init (None leaves the tap registers untouched).
+    /// Also enables/disables the SINGLE_TAP and DOUBLE_TAP interrupts as requested by the config
+    pub fn set_tap_config(&mut self, tap: Option<TapConfig>){
+        self.tap = tap;
+        self.interrupt_enable.remove(InterruptSet::SINGLE_TAP | InterruptSet::DOUBLE_TAP);
+        if let Some(tap) = tap {
+            self.interrupt_enable.insert(tap.interrupts());
+        }
+    }
+
+    pub fn get_tap_config(&self) -> Option<TapConfig>{
+        self.tap
+    }
+
+    /// activity/inactivity configuration written by init (None leaves the registers untouched).
+    /// Also enables/disables the ACTIVITY and INACTIVITY interrupts as requested by the config
+    pub fn set_activity_config(&mut self, activity: Option<ActivityConfig>){
+        self.activity = activity;
+        self.interrupt_enable.remove(InterruptSet::ACTIVITY | InterruptSet::INACTIVITY);
+        if let Some(activity) = activity {
+            self.interrupt_enable.insert(activity.interrupts());
+        }
+    }
+
+    pub fn get_activity_config(&self) -> Option<ActivityConfig>{
+        self.activity
+    }
+
+    /// free fall configuration written by init (None leaves the free fall registers untouched).
+    /// Also enables/disables the FREE_FALL interrupt as requested by the config
+    pub fn set_free_fall_config(&mut self, free_fall: Option<FreeFallConfig>){
+        self.free_fall = free_fall;
+        self.interrupt_enable.remove(InterruptSet::FREE_FALL);
+        if let Some(free_fall) = free_fall {
+            self.interrupt_enable.insert(free_fall.interrupts());
+        }
+    }
+
+    pub fn get_free_fall_config(&self) -> Option<FreeFallConfig>{
+        self.free_fall
+    }
+
+    /// enables (enabled = true) or disables every interrupt in interrupts, leaving the rest unchanged
+    pub fn set_interrupts_enabled(&mut self, interrupts: InterruptSet, enabled: bool){
+        self.interrupt_enable.set(interrupts, enabled);
+    }
+
+    pub fn get_enabled_interrupts(&self) -> InterruptSet{
+        self.interrupt_enable
+    }
+
+    /// routes every interrupt in interrupts to the given pin, leaving the rest unchanged
+    pub fn map_interrupts(&mut self, interrupts: InterruptSet, pin: InterruptPin){
+        self.interrupt_map.set(interrupts, pin == InterruptPin::INT2);
+    }
+
+    /// interrupts routed to the INT2 pin (all others are routed to INT1)
+    pub fn get_int2_interrupts(&self) -> InterruptSet{
+        self.interrupt_map
+    }
+
 }
 
 
+#[cfg(test)]
+mod tests {
+    use super::*;
 
+    #[test]
+    fn low_power_odr_range() {
+        let mut settings = ADXL343Settings::default();
+        settings.set_odr(OutputDataRate::Hz800);
+        assert!(settings.low_power_odr_supported());
 
+        settings.set_low_power_mode(true);
+        assert!(!settings.low_power_odr_supported());
+        settings.set_odr(OutputDataRate::Hz6_25);
+        assert!(!settings.low_power_odr_supported());
+        settings.set_odr(OutputDataRate::Hz400);
+        assert!(settings.low_power_odr_supported());
+        settings.set_odr(OutputDataRate::Hz12_5);
+        assert!(settings.low_power_odr_supported());
+        assert_eq!(settings.BW_RATE_reg_value(), 0b1_0111);
+    }
+
+    #[test]
+    fn decode_register_values() {
+        let mut settings = ADXL343Settings::default();
+        settings.set_odr(OutputDataRate::Hz400);
+        settings.set_low_power_mode(true);
+        settings.set_range(AccelRange::_8g);
+        settings.set_resolution(FullRes::full_res);
+        settings.set_fifo_mode(FIFOMode::STREAM);
+        settings.set_fifo_samples(16);
+        settings.set_offsets([-3, 0, 5]);
+        settings.set_tap_config(Some(TapConfig::default().double_tap(true)));
+        settings.set_activity_config(Some(ActivityConfig::default()));
+        settings.map_interrupts(InterruptSet::ACTIVITY, InterruptPin::INT2);
+        settings.set_wakeup_rate(SLEEP_MODE_ODR::_2Hz);
+        settings.set_link(true);
+        settings.toggle_measurement_mode();
+
+        let mut registers = [0u8; 0x40];
+        for (address, value) in settings.init_register_writes().unwrap().iter() {
+            registers[address as usize] = value;
+        }
+        registers[POWER_CTL_ADDR as usize] = settings.POWER_CTL_reg_value();
+
+        let decoded = ADXL343Settings::from_register_values(|address| registers[address as usize]);
+        assert_eq!(decoded, settings);
+    }
+
+    #[test]
+    fn decode_all_formats() {
+        use AccelRange::*;
+        use FullRes::*;
+        use Alignment::*;
+        //[DATA_0, DATA_1] of a -1 g reading and the expected lsb's, for every combination
+        let table: [(AccelRange, FullRes, Alignment, [u8; 2], i16); 16] = [
+            (_2g,  _10bit_res, right, [0x00, 0xFF], -256),
+            (_4g,  _10bit_res, right, [0x80, 0xFF], -128),
+            (_8g,  _10bit_res, right, [0xC0, 0xFF], -64),
+            (_16g, _10bit_res, right, [0xE0, 0xFF], -32),
+            (_2g,  _10bit_res, left,  [0x00, 0xC0], -256),
+            (_4g,  _10bit_res, left,  [0x00, 0xE0], -128),
+            (_8g,  _10bit_res, left,  [0x00, 0xF0], -64),
+            (_16g, _10bit_res, left,  [0x00, 0xF8], -32),
+            (_2g,  full_res,   right, [0x00, 0xFF], -256),
+            (_4g,  full_res,   right, [0x00, 0xFF], -256),
+            (_8g,  full_res,   right, [0x00, 0xFF], -256),
+            (_16g, full_res,   right, [0x00, 0xFF], -256),
+            (_2g,  full_res,   left,  [0x00, 0xC0], -256),
+            (_4g,  full_res,   left,  [0x00, 0xE0], -256),
+            (_8g,  full_res,   left,  [0x00, 0xF0], -256),
+            (_16g, full_res,   left,  [0x00, 0xF8], -256),
+        ];
+        for (range, resolution, justification, data, expected) in table {
+            let mut settings = ADXL343Settings::default();
+            settings.set_range(range);
+            settings.set_resolution(resolution);
+            settings.set_justification(justification);
+            assert_eq!(settings.axis_value_raw(data), expected, "{:?} {:?} {:?}", range, resolution, justification);
+            assert_eq!(settings.raw_axes_to_g([expected; 3]), [-1.0; 3]);
+
+            //full scale readings in both directions
+            let bits = settings.resolution_to_bits();
+            for value in [(1i16 << (bits - 1)) - 1, -(1i16 << (bits - 1)), 1, -1, 0] {
+                let encoded = match justification {
+                    right => value,
+                    left => value << (16 - bits)
+                };
+                assert_eq!(settings.axis_value_raw(encoded.to_le_bytes()), value);
+            }
+        }
+
+        let settings = ADXL343Settings::default();
+        assert_eq!(settings.sample_to_raw_axes([0x01, 0x00, 0xFF, 0xFF, 0x00, 0x01]), [1, -1, 256]);
+    }
+
+    #[test]
+    fn milli_g_matches_float() {
+        for range in [AccelRange::_2g, AccelRange::_4g, AccelRange::_8g, AccelRange::_16g] {
+            for resolution in [FullRes::_10bit_res, FullRes::full_res] {
+                let mut settings = ADXL343Settings::default();
+                settings.set_range(range);
+                settings.set_resolution(resolution);
+                let bits = settings.resolution_to_bits();
+                for raw in -(1i16 << (bits - 1))..(1i16 << (bits - 1)) {
+                    let g = settings.raw_axes_to_g([raw; 3])[0] * 1000.0;
+                    let expected = match g < 0.0 {
+                        true => (g - 0.5) as i32,
+                        false => (g + 0.5) as i32
+                    };
+                    assert_eq!(settings.raw_axes_to_mg([raw; 3]), [expected; 3]);
+                    #[cfg(feature = "fixed")]
+                    assert_eq!(settings.raw_axes_to_fixed([raw; 3])[0].to_num::<f32>(), g / 1000.0);
+                }
+            }
+        }
+        //ties round away from zero: 16/256 g = 62.5 mg, 48/256 g = 187.5 mg, 2/32 g = 62.5 mg
+        let mut settings = ADXL343Settings::default();
+        assert_eq!(settings.raw_axes_to_mg([16, -16, 48]), [63, -63, 188]);
+        assert_eq!(settings.raw_axes_to_mg([1, -2, 256]), [4, -8, 1000]);
+        settings.set_range(AccelRange::_16g);
+        assert_eq!(settings.raw_axes_to_mg([2, -2, 1]), [63, -63, 31]);
+    }
 
+    #[test]
+    fn supply_current() {
+        let mut settings = ADXL343Settings::default();
+        assert_eq!(settings.supply_current_ua(), 140);
+        settings.set_low_power_mode(true);
+        assert_eq!(settings.supply_current_ua(), 50);
 
+        settings.set_odr(OutputDataRate::Hz400);
+        assert_eq!(settings.supply_current_ua(), 90);
+        settings.set_odr(OutputDataRate::Hz1600);
+        assert_eq!(settings.supply_current_ua(), 90);
+        settings.set_odr(OutputDataRate::Hz0_10);
+        assert_eq!(settings.supply_current_ua(), 23);
 
+        settings.set_low_power_mode(false);
+        settings.set_odr(OutputDataRate::Hz12_5);
+        assert_eq!(settings.supply_current_ua(), 50);
+    }
+}
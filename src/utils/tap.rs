@@ -0,0 +1,191 @@
+#![allow(non_snake_case)]
+use derive_setters::Setters;
+
+use crate::registers::{
+    THRESH_TAP_ADDR, DUR_ADDR, LATENT_ADDR, WINDOW_ADDR, TAP_AXES_ADDR,
+    accel_configs::{ACT_TAP_STATUS, TAP_AXES}
+};
+use super::{axes::{Axis, AxisSet}, interrupts::InterruptSet, to_register_value};
+
+/// THRESH_TAP scale factor (62.5 mg/LSB)
+pub const THRESH_TAP_G_PER_LSB: f32 = 0.0625;
+/// DUR scale factor (625 µs/LSB)
+pub const DUR_MS_PER_LSB: f32 = 0.625;
+/// LATENT scale factor (1.25 ms/LSB)
+pub const LATENT_MS_PER_LSB: f32 = 1.25;
+/// WINDOW scale factor (1.25 ms/LSB)
+pub const WINDOW_MS_PER_LSB: f32 = 1.25;
+
+/// Single and double tap detection configuration. Quantities are given in g's and milliseconds,
+/// and converted into register values when written to the device.
+/// 
+/// # Fields
+/// 
+/// - `threshold_g` - minimum acceleration of a tap (THRESH_TAP)
+/// - `duration_ms` - maximum time an event may stay above threshold_g to qualify as a tap (DUR),
+///   0 disables both tap functions
+/// - `latency_ms` - wait time between the first tap and the start of the window (LATENT),
+///   0 disables double tap
+/// - `window_ms` - time after the latency in which a second tap may start (WINDOW), 
+///   0 disables double tap
+/// - `axes` - axes participating in tap detection (TAP_AXES)
+/// - `suppress` - suppress double taps if acceleration above threshold_g is present between taps
+/// - `single_tap` - enable the SINGLE_TAP interrupt
+/// - `double_tap` - enable the DOUBLE_TAP interrupt
+#[derive(Clone, Copy, Debug, PartialEq, Setters)]
+pub struct TapConfig {
+    pub threshold_g: f32,
+    pub duration_ms: f32,
+    pub latency_ms: f32,
+    pub window_ms: f32,
+    pub axes: AxisSet,
+    pub suppress: bool,
+    pub single_tap: bool,
+    pub double_tap: bool
+}
+
+impl Default for TapConfig {
+    fn default() -> Self {
+        TapConfig {
+            threshold_g: 3.0,
+            duration_ms: 10.0,
+            latency_ms: 20.0,
+            window_ms: 200.0,
+            axes: AxisSet::ALL,
+            suppress: false,
+            single_tap: true,
+            double_tap: false
+        }
+    }
+}
+
+impl TapConfig {
+    /// THRESH_TAP register value, None if threshold_g is negative or above 15.94 g
+    pub fn THRESH_TAP_reg_value(&self) -> Option<u8> {
+        to_register_value(self.threshold_g, THRESH_TAP_G_PER_LSB)
+    }
+
+    /// DUR register value, None if duration_ms is negative or above 159.4 ms
+    pub fn DUR_reg_value(&self) -> Option<u8> {
+        to_register_value(self.duration_ms, DUR_MS_PER_LSB)
+    }
+
+    /// LATENT register value, None if latency_ms is negative or above 318.75 ms
+    pub fn LATENT_reg_value(&self) -> Option<u8> {
+        to_register_value(self.latency_ms, LATENT_MS_PER_LSB)
+    }
+
+    /// WINDOW register value, None if window_ms is negative or above 318.75 ms
+    pub fn WINDOW_reg_value(&self) -> Option<u8> {
+        to_register_value(self.window_ms, WINDOW_MS_PER_LSB)
+    }
+
+    pub fn TAP_AXES_reg_value(&self) -> u8 {
+        TAP_AXES::new()
+            .with_tap_x_enable(self.axes.x as u8)
+            .with_tap_y_enable(self.axes.y as u8)
+            .with_tap_z_enable(self.axes.z as u8)
+            .with_suppress(self.suppress as u8).into_bytes()[0]
+    }
+
+    /// decodes the THRESH_TAP, DUR, LATENT, WINDOW and TAP_AXES register values, taking the 
+    /// single_tap and double_tap flags from the enabled interrupts
+    pub fn from_registers(thresh_tap: u8, dur: u8, latent: u8, window: u8, tap_axes: TAP_AXES, 
+        enabled: InterruptSet) -> Self {
+        TapConfig {
+            threshold_g: thresh_tap as f32 * THRESH_TAP_G_PER_LSB,
+            duration_ms: dur as f32 * DUR_MS_PER_LSB,
+            latency_ms: latent as f32 * LATENT_MS_PER_LSB,
+            window_ms: window as f32 * WINDOW_MS_PER_LSB,
+            axes: AxisSet::from_bits(tap_axes.tap_x_enable(), tap_axes.tap_y_enable(), tap_axes.tap_z_enable()),
+            suppress: tap_axes.suppress() != 0,
+            single_tap: enabled.contains(InterruptSet::SINGLE_TAP),
+            double_tap: enabled.contains(InterruptSet::DOUBLE_TAP)
+        }
+    }
+
+    /// (address, value) of every tap register, None if a value does not fit in its register
+    pub(crate) fn register_writes(&self) -> Option<[(u8, u8); 5]> {
+        Some([
+            (THRESH_TAP_ADDR, self.THRESH_TAP_reg_value()?),
+            (DUR_ADDR, self.DUR_reg_value()?),
+            (LATENT_ADDR, self.LATENT_reg_value()?),
+            (WINDOW_ADDR, self.WINDOW_reg_value()?),
+            (TAP_AXES_ADDR, self.TAP_AXES_reg_value())
+        ])
+    }
+
+    /// the tap interrupts this configuration enables (out of SINGLE_TAP and DOUBLE_TAP)
+    pub fn interrupts(&self) -> InterruptSet {
+        let mut interrupts = InterruptSet::empty();
+        interrupts.set(InterruptSet::SINGLE_TAP, self.single_tap);
+        interrupts.set(InterruptSet::DOUBLE_TAP, self.double_tap);
+        interrupts
+    }
+}
+
+/// Kind of tap reported in INT_SOURCE
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TapKind {
+    Single,
+    Double
+}
+
+/// A detected tap, along with the first axis involved in it (from ACT_TAP_STATUS)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TapEvent {
+    pub kind: TapKind,
+    pub axis: Option<Axis>
+}
+
+impl TapEvent {
+    /// Decodes INT_SOURCE's tap flags and ACT_TAP_STATUS's tap source bits. A double tap is 
+    /// reported over a single tap when both flags are set
+    pub fn from_registers(single_tap: u8, double_tap: u8, status: ACT_TAP_STATUS) -> Option<Self> {
+        let kind = match (single_tap, double_tap) {
+            (_, 1) => TapKind::Double,
+            (1, _) => TapKind::Single,
+            _ => return None
+        };
+        let axis = AxisSet::from_bits(
+            status.tap_x_source(),
+            status.tap_y_source(),
+            status.tap_z_source()
+        ).first();
+        Some(TapEvent { kind, axis })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tap_register_conversion(){
+        let config = TapConfig::default()
+            .threshold_g(3.0)
+            .duration_ms(10.0)
+            .latency_ms(20.0)
+            .window_ms(318.75);
+        assert_eq!(config.THRESH_TAP_reg_value(), Some(48));
+        assert_eq!(config.DUR_reg_value(), Some(16));
+        assert_eq!(config.LATENT_reg_value(), Some(16));
+        assert_eq!(config.WINDOW_reg_value(), Some(255));
+        assert_eq!(config.threshold_g(16.0).THRESH_TAP_reg_value(), None);
+        assert_eq!(config.duration_ms(-1.0).DUR_reg_value(), None);
+    }
+
+    #[test]
+    fn tap_event_decoding(){
+        let status = ACT_TAP_STATUS::new().with_tap_z_source(1);
+        assert_eq!(
+            TapEvent::from_registers(1, 1, status),
+            Some(TapEvent { kind: TapKind::Double, axis: Some(Axis::Z) })
+        );
+        assert_eq!(
+            TapEvent::from_registers(1, 0, ACT_TAP_STATUS::new()),
+            Some(TapEvent { kind: TapKind::Single, axis: None })
+        );
+        assert_eq!(TapEvent::from_registers(0, 0, status), None);
+    }
+}
@@ -0,0 +1,198 @@
+use core::fmt::Display;
+use crate::registers::accel_configs::{OutputDataRate, AccelRange, FullRes, FIFOMode};
+use super::settings::ADXL343Settings;
+
+/// Bit times on the I2c bus to read one sample: start (1), address + write (9, with the ack), 
+/// DATAX0 (9), repeated start (1), address + read (9), 6 data bytes with their ack or final nack 
+/// (54) and stop (1), 84 in total
+pub const I2C_BITS_PER_SAMPLE: u32 = 84;
+
+/// Maximum number of issues in a ValidationReport (each kind of issue is reported at most once)
+pub const MAX_CONFIG_ISSUES: usize = 5;
+
+/// Whether an issue makes the configuration unusable (Error) or only degrades it (Warning)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Severity {
+    Warning,
+    Error
+}
+
+/// Problem found in a configuration by ADXL343Settings::validate
+///
+/// # Variants
+///
+/// - `InsufficientBusBandwidth` - reading every sample needs more bits per second than the bus speed.
+///   An error in bypass and stream mode, where samples are lost whatever the reads, a warning in FIFO 
+///   and trigger mode, which capture up to 32 samples once (one_shot_capture) to be read afterwards
+/// - `LowPowerOdrUnsupported` - low power mode outside of 12.5 Hz - 400 Hz, an error (init rejects it)
+/// - `ReducedResolution` - 10 bit resolution above the 2 g range (8, 16 or 32 mg/lsb instead of 4 mg/lsb
+///   in full resolution), a warning
+/// - `LsbAlwaysZero` - at 1600 Hz and 3200 Hz the lsb of the output is always 0, a warning
+/// - `LowOdrOffsetDrift` - at 6.25 Hz and below in the 2 g range, the datasheet lists an offset 
+///   shift from the offset at 12.5 Hz and above (a fraction of an LSB in the other ranges), a warning
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ConfigIssue {
+    InsufficientBusBandwidth { required_bits_per_s: u32, bus_speed_hz: u32, one_shot_capture: bool },
+    LowPowerOdrUnsupported { odr: OutputDataRate },
+    ReducedResolution { range: AccelRange },
+    LsbAlwaysZero { odr: OutputDataRate },
+    LowOdrOffsetDrift { odr: OutputDataRate }
+}
+
+impl ConfigIssue {
+    pub fn severity(&self) -> Severity {
+        match self {
+            ConfigIssue::InsufficientBusBandwidth { one_shot_capture: false, .. } => Severity::Error,
+            ConfigIssue::LowPowerOdrUnsupported { .. } => Severity::Error,
+            _ => Severity::Warning
+        }
+    }
+}
+
+impl Display for ConfigIssue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ConfigIssue::InsufficientBusBandwidth { required_bits_per_s, bus_speed_hz, .. } => {
+                write!(f, "Reading every sample needs {} bit/s, the bus runs at {} Hz", required_bits_per_s, bus_speed_hz)
+            },
+            ConfigIssue::LowPowerOdrUnsupported { odr } => {
+                write!(f, "Low power mode is not supported at {} Hz", odr.hz())
+            },
+            ConfigIssue::ReducedResolution { range } => {
+                write!(f, "10 bit resolution at the {:?} range, full resolution keeps 4 mg/lsb", range)
+            },
+            ConfigIssue::LsbAlwaysZero { odr } => {
+                write!(f, "The lsb of the output is always 0 at {} Hz", odr.hz())
+            },
+            ConfigIssue::LowOdrOffsetDrift { odr } => {
+                write!(f, "Offset shift in the 2 g range at {} Hz, compared to 12.5 Hz and above", odr.hz())
+            }
+        }
+    }
+}
+
+/// Fixed-capacity list of the issues found in a configuration, see ADXL343Settings::validate
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct ValidationReport {
+    issues: [Option<ConfigIssue>; MAX_CONFIG_ISSUES]
+}
+
+impl ValidationReport {
+    /// Checks settings, for an I2c bus running at bus_speed_hz (e.g. 100_000 or 400_000)
+    pub fn new(settings: &ADXL343Settings, bus_speed_hz: u32) -> Self {
+        let mut report = ValidationReport::default();
+        let odr = settings.get_odr();
+
+        let required_bits_per_s = (odr.hz() * I2C_BITS_PER_SAMPLE as f32) as u32;
+        if required_bits_per_s > bus_speed_hz {
+            let one_shot_capture = matches!(settings.get_fifo_mode(), FIFOMode::FIFO | FIFOMode::TRIGGER);
+            report.push(ConfigIssue::InsufficientBusBandwidth { required_bits_per_s, bus_speed_hz, one_shot_capture });
+        }
+        if !settings.low_power_odr_supported() {
+            report.push(ConfigIssue::LowPowerOdrUnsupported { odr });
+        }
+        if settings.get_resolution() == FullRes::_10bit_res && settings.get_range() != AccelRange::_2g {
+            report.push(ConfigIssue::ReducedResolution { range: settings.get_range() });
+        }
+        if matches!(odr, OutputDataRate::Hz3200 | OutputDataRate::Hz1600) {
+            report.push(ConfigIssue::LsbAlwaysZero { odr });
+        }
+        if odr.hz() <= OutputDataRate::Hz6_25.hz() && settings.get_range() == AccelRange::_2g {
+            report.push(ConfigIssue::LowOdrOffsetDrift { odr });
+        }
+        report
+    }
+
+    fn push(&mut self, issue: ConfigIssue) {
+        if let Some(slot) = self.issues.iter_mut().find(|slot| slot.is_none()) {
+            *slot = Some(issue);
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ConfigIssue> {
+        self.issues.iter().flatten()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ConfigIssue> {
+        self.iter().filter(|issue| issue.severity() == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &ConfigIssue> {
+        self.iter().filter(|issue| issue.severity() == Severity::Warning)
+    }
+
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// true if no issue (error or warning) was found
+    pub fn is_empty(&self) -> bool {
+        self.issues[0].is_none()
+    }
+
+    /// true if no error was found, the configuration can be used (possibly with warnings)
+    pub fn is_ok(&self) -> bool {
+        self.errors().next().is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_settings(){
+        let report = ADXL343Settings::default().validate(100_000);
+        assert!(report.is_empty());
+        assert!(report.is_ok());
+    }
+
+    #[test]
+    fn bus_bandwidth(){
+        let mut settings = ADXL343Settings::default();
+        settings.set_odr(OutputDataRate::Hz3200);
+        settings.set_resolution(FullRes::full_res);
+        let report = settings.validate(100_000);
+        assert_eq!(report.errors().next(), Some(&ConfigIssue::InsufficientBusBandwidth {
+            required_bits_per_s: 268_800, bus_speed_hz: 100_000, one_shot_capture: false
+        }));
+        assert!(!report.is_ok());
+
+        //stream mode keeps sampling, so the FIFO only delays the loss of samples
+        settings.set_fifo_mode(FIFOMode::STREAM);
+        assert!(!settings.validate(100_000).is_ok());
+
+        for mode in [FIFOMode::FIFO, FIFOMode::TRIGGER] {
+            settings.set_fifo_mode(mode);
+            let report = settings.validate(100_000);
+            assert!(report.is_ok());
+            assert_eq!(report.len(), 2); //bandwidth and lsb always zero warnings
+        }
+
+        settings.set_odr(OutputDataRate::Hz800);
+        assert!(settings.validate(100_000).is_empty());
+    }
+
+    #[test]
+    fn configuration_warnings(){
+        let mut settings = ADXL343Settings::default();
+        settings.set_range(AccelRange::_16g);
+        settings.set_odr(OutputDataRate::Hz6_25);
+        let report = settings.validate(400_000);
+        assert!(report.is_ok());
+        let mut warnings = report.warnings();
+        assert_eq!(warnings.next(), Some(&ConfigIssue::ReducedResolution { range: AccelRange::_16g }));
+        assert_eq!(warnings.next(), None);
+
+        //the low ODR offset shift only matters in the 2 g range
+        settings.set_range(AccelRange::_2g);
+        let report = settings.validate(400_000);
+        let mut warnings = report.warnings();
+        assert_eq!(warnings.next(), Some(&ConfigIssue::LowOdrOffsetDrift { odr: OutputDataRate::Hz6_25 }));
+        assert_eq!(warnings.next(), None);
+
+        settings.set_low_power_mode(true);
+        let report = settings.validate(400_000);
+        assert_eq!(report.errors().next(), Some(&ConfigIssue::LowPowerOdrUnsupported { odr: OutputDataRate::Hz6_25 }));
+    }
+}
@@ -0,0 +1,254 @@
+//host tests of the driver against the software model of the device (no hardware required)
+use adxl343_i2c_generic::{
+    ADXL343Interface, ADXL343Error, ADXL343Settings, ADXL343, EmulatedADXL343, I2cAddress, I2cBus,
+    CalibrationOrientation, FIFOMode, InterruptSet, OutputDataRate,
+};
+use embedded_hal::i2c::ErrorKind;
+
+//the interface borrows the device, so tests can tick it or change its acceleration in between
+type SensorInterface<'a> = ADXL343Interface<I2cBus<&'a mut EmulatedADXL343>>;
+
+fn measuring_sensor(device: &mut EmulatedADXL343, settings: ADXL343Settings) -> SensorInterface<'_> {
+    let mut sensor = ADXL343Interface::new(device);
+    sensor.with_settings(settings).unwrap();
+    sensor.init().unwrap();
+    sensor.begin_measurements().unwrap();
+    sensor
+}
+
+fn assert_accel_eq(accel: [f32; 3], expected: [f32; 3]) {
+    for (axis, expected) in accel.iter().zip(expected) {
+        assert!((axis - expected).abs() < 0.01, "{:?} != {:?}", accel, expected);
+    }
+}
+
+#[test]
+fn confirm_device() {
+    let mut sensor = ADXL343Interface::new(EmulatedADXL343::new());
+    sensor.confirm_device().unwrap();
+
+    let mut sensor = ADXL343Interface::new_with_address(EmulatedADXL343::new(), I2cAddress::Alternate);
+    assert!(matches!(sensor.confirm_device(), Err(ADXL343Error::Interface(ErrorKind::NoAcknowledge(_)))));
+}
+
+#[test]
+fn read_accel() {
+    let mut device = EmulatedADXL343::new();
+    let mut sensor = measuring_sensor(&mut device, ADXL343Settings::default());
+    assert_accel_eq(sensor.read_accel().unwrap(), [0.0, 0.0, 1.0]);
+
+    device.set_acceleration([0.5, -1.25, 0.0]);
+    let mut sensor = ADXL343Interface::new(&mut device);
+    sensor.read_settings().unwrap();
+    assert_accel_eq(sensor.read_accel().unwrap(), [0.5, -1.25, 0.0]);
+    assert_eq!(sensor.read_accel_mg().unwrap(), [500, -1250, 0]);
+}
+
+#[test]
+fn settings_round_trip() {
+    let mut settings = ADXL343Settings::default();
+    settings.set_odr(OutputDataRate::Hz800);
+    settings.set_fifo_mode(FIFOMode::STREAM);
+    settings.set_fifo_samples(10);
+    let mut device = EmulatedADXL343::new();
+    let mut sensor = measuring_sensor(&mut device, settings);
+    sensor.verify_configuration().unwrap();
+
+    let written = *sensor.settings();
+    assert_eq!(sensor.read_settings().unwrap(), written);
+    assert_eq!(sensor.dump_registers().unwrap().settings(), written);
+}
+
+#[test]
+fn calibrate_offsets() {
+    let mut device = EmulatedADXL343::new();
+    device.set_acceleration([0.125, -0.0625, 1.1]);
+    let mut sensor = measuring_sensor(&mut device, ADXL343Settings::default());
+
+    let offsets = sensor.calibrate_offsets(CalibrationOrientation::ZUp, 8).unwrap();
+    assert_accel_eq(offsets, [-0.125, 0.0625, -0.1]);
+    assert_accel_eq(sensor.read_accel().unwrap(), [0.0, 0.0, 1.0]);
+}
+
+#[test]
+fn calibrate_offsets_timeout() {
+    //the FIFO is never ticked, so DATA_READY stays clear
+    let mut settings = ADXL343Settings::default();
+    settings.set_odr(OutputDataRate::Hz3200);
+    settings.set_fifo_mode(FIFOMode::FIFO);
+    settings.set_offsets([4, -2, 1]);
+    let mut device = EmulatedADXL343::new();
+    let mut sensor = measuring_sensor(&mut device, settings);
+
+    let result = sensor.calibrate_offsets(CalibrationOrientation::ZUp, 8);
+    assert!(matches!(result, Err(ADXL343Error::Timeout)));
+    assert_eq!(sensor.settings().get_offsets(), [4, -2, 1]);
+    sensor.verify_configuration().unwrap();
+}
+
+#[test]
+fn fifo_watermark() {
+    let mut settings = ADXL343Settings::default();
+    settings.set_fifo_mode(FIFOMode::FIFO);
+    settings.set_fifo_samples(4);
+    let mut device = EmulatedADXL343::new();
+    device.set_acceleration([0.0, 1.0, 0.0]);
+    let mut sensor = measuring_sensor(&mut device, settings);
+    assert_eq!(sensor.fifo_entries().unwrap(), 0);
+
+    device.tick(6);
+    let mut sensor = ADXL343Interface::new(&mut device);
+    sensor.read_settings().unwrap();
+    assert!(sensor.read_interrupt_source().unwrap().contains(InterruptSet::WATERMARK));
+
+    let mut buffer = [[0f32; 3]; 8];
+    assert_eq!(sensor.read_fifo_accel(&mut buffer).unwrap(), 6);
+    for accel in &buffer[..6] {
+        assert_accel_eq(*accel, [0.0, 1.0, 0.0]);
+    }
+    assert_eq!(sensor.fifo_entries().unwrap(), 0);
+}
+
+#[test]
+fn typestate_driver() {
+    let sensor = ADXL343::new(EmulatedADXL343::new()).init().unwrap();
+    let mut sensor = sensor.begin_measurements().unwrap();
+    assert_accel_eq(sensor.read_accel().unwrap(), [0.0, 0.0, 1.0]);
+    let sensor = sensor.turn_off_measurements().unwrap();
+    assert!(!sensor.settings().in_measurement_mode());
+}
+
+#[test]
+fn all_formats() {
+    use adxl343_i2c_generic::{AccelRange, Alignment, FullRes};
+    for range in [AccelRange::_2g, AccelRange::_4g, AccelRange::_8g, AccelRange::_16g] {
+        for resolution in [FullRes::_10bit_res, FullRes::full_res] {
+            for justification in [Alignment::right, Alignment::left] {
+                let mut settings = ADXL343Settings::default();
+                settings.set_range(range);
+                settings.set_resolution(resolution);
+                settings.set_justification(justification);
+                let mut device = EmulatedADXL343::new();
+                device.set_acceleration([1.5, -1.0, 0.25]);
+                let mut sensor = measuring_sensor(&mut device, settings);
+                let accel = sensor.read_accel().unwrap();
+                assert_accel_eq(accel, [1.5, -1.0, 0.25]);
+            }
+        }
+    }
+}
+
+#[test]
+fn self_test() {
+    let mut device = EmulatedADXL343::new();
+    let mut sensor = measuring_sensor(&mut device, ADXL343Settings::default());
+    let report = sensor.run_self_test(2.5, 8).unwrap();
+    assert!(report.passed_all(), "{:?}", report);
+    assert_eq!(sensor.settings(), &{
+        let mut settings = ADXL343Settings::default();
+        settings.toggle_measurement_mode();
+        settings
+    });
+}
+
+#[test]
+fn self_test_timeout() {
+    let mut settings = ADXL343Settings::default();
+    settings.set_odr(OutputDataRate::Hz3200);
+    settings.set_fifo_mode(FIFOMode::FIFO);
+    let mut device = EmulatedADXL343::new();
+    let data_format = measuring_sensor(&mut device, settings).settings().DATA_FORMAT_reg_value();
+
+    //enough samples for the self-test off average, the wait times out with the self-test force on
+    device.tick(8);
+    let mut sensor = ADXL343Interface::new(&mut device);
+    sensor.read_settings().unwrap();
+    assert!(matches!(sensor.run_self_test(2.5, 8), Err(ADXL343Error::Timeout)));
+    assert!(!sensor.settings().get_self_test());
+    assert_eq!(sensor.settings().DATA_FORMAT_reg_value(), data_format);
+    sensor.verify_configuration().unwrap();
+    assert_eq!(device.register(0x31), data_format); //DATA_FORMAT
+    assert_eq!(device.register(0x2C), 0x0F); //BW_RATE, back to 3200 Hz after the 100 Hz test
+}
+
+#[test]
+fn fifo_burst_drain() {
+    let mut settings = ADXL343Settings::default();
+    settings.set_fifo_mode(FIFOMode::STREAM);
+    let mut device = EmulatedADXL343::new();
+    measuring_sensor(&mut device, settings);
+    device.tick(40);
+
+    let mut sensor = ADXL343Interface::new(&mut device);
+    sensor.read_settings().unwrap();
+    let mut samples = [[0u8; 6]; 40];
+    assert_eq!(sensor.read_fifo_samples(&mut samples).unwrap(), 32);
+    assert!(samples[..32].iter().all(|sample| *sample == [0x00, 0x00, 0x00, 0x00, 0x00, 0x01]));
+    assert_eq!(sensor.fifo_entries().unwrap(), 0);
+}
+
+#[test]
+fn init_validated() {
+    use adxl343_i2c_generic::ConfigIssue;
+    let mut settings = ADXL343Settings::default();
+    settings.set_odr(OutputDataRate::Hz3200);
+    let mut sensor = ADXL343Interface::new(EmulatedADXL343::new());
+    sensor.with_settings(settings).unwrap();
+    assert!(matches!(
+        sensor.init_validated(100_000),
+        Err(ADXL343Error::InvalidConfiguration(ConfigIssue::InsufficientBusBandwidth { .. }))
+    ));
+
+    //stream mode cannot be drained fast enough either
+    settings.set_fifo_mode(FIFOMode::STREAM);
+    sensor.with_settings(settings).unwrap();
+    assert!(matches!(
+        sensor.init_validated(100_000),
+        Err(ADXL343Error::InvalidConfiguration(ConfigIssue::InsufficientBusBandwidth { one_shot_capture: false, .. }))
+    ));
+
+    //a one-shot FIFO capture turns the bandwidth error into a warning
+    settings.set_fifo_mode(FIFOMode::FIFO);
+    let sensor = ADXL343::new(EmulatedADXL343::new()).with_settings(settings).unwrap();
+    let sensor = sensor.init_validated(100_000).unwrap();
+    assert_eq!(sensor.settings().get_odr(), OutputDataRate::Hz3200);
+}
+
+#[test]
+fn events_not_lost() {
+    use adxl343_i2c_generic::{TapEvent, TapKind};
+    let mut settings = ADXL343Settings::default();
+    settings.set_interrupts_enabled(InterruptSet::SINGLE_TAP | InterruptSet::FREE_FALL | InterruptSet::ACTIVITY, true);
+    let mut device = EmulatedADXL343::new();
+    measuring_sensor(&mut device, settings);
+    device.trigger_events(InterruptSet::SINGLE_TAP | InterruptSet::FREE_FALL | InterruptSet::ACTIVITY);
+
+    //adopting the settings does not read INT_SOURCE, and polling taps keeps the free fall and 
+    //activity events, which the read of INT_SOURCE cleared
+    let mut sensor = ADXL343Interface::new(&mut device);
+    sensor.read_settings().unwrap();
+    assert_eq!(sensor.read_tap_event().unwrap(), Some(TapEvent { kind: TapKind::Single, axis: None }));
+    assert_eq!(sensor.read_tap_event().unwrap(), None);
+    assert!(sensor.read_free_fall().unwrap());
+    assert!(!sensor.read_free_fall().unwrap());
+
+    let events = sensor.read_events().unwrap();
+    assert!(events.activity_status().activity);
+    assert!(!events.source.intersects(InterruptSet::SINGLE_TAP | InterruptSet::FREE_FALL));
+    assert!(!sensor.read_events().unwrap().activity_status().activity);
+}
+
+#[test]
+fn events_survive_verify_configuration() {
+    use adxl343_i2c_generic::{TapEvent, TapKind};
+    let mut settings = ADXL343Settings::default();
+    settings.set_interrupts_enabled(InterruptSet::DOUBLE_TAP | InterruptSet::FREE_FALL, true);
+    let mut device = EmulatedADXL343::new();
+    device.trigger_events(InterruptSet::DOUBLE_TAP | InterruptSet::FREE_FALL);
+
+    //verify_configuration does not read INT_SOURCE, which would clear both events
+    let mut sensor = measuring_sensor(&mut device, settings);
+    sensor.verify_configuration().unwrap();
+    assert_eq!(sensor.read_tap_event().unwrap(), Some(TapEvent { kind: TapKind::Double, axis: None }));
+    assert!(sensor.read_free_fall().unwrap());
+}
@@ -8,11 +8,12 @@ mod helper;
 use linux_embedded_hal::{I2cdev, I2CError};
 use adxl343::{
 	adxl343_interface::{ADXL343Interface, ADXL343Error},
+	bus::I2cBus,
 	registers::accel_configs::{OutputDataRate, Alignment},
 	utils::settings::ADXL343Settings
 };
 										
-type SensorInterface = ADXL343Interface<I2cdev>;
+type SensorInterface = ADXL343Interface<I2cBus<I2cdev>>;
 type SensorError = ADXL343Error<I2CError>;
 
 //ensures that we are speaking to the device with id 0x53 (ie the adxl343)
@@ -114,7 +115,7 @@ fn create_sensor_with_sample_rate(mut sensor: SensorInterface, odr: OutputDataRa
 	sensor.turn_off_measurements()?;
 	let (i2c, mut settings) = sensor.destroy();
 	settings.set_odr(odr);
-	let mut sensor = ADXL343Interface::<I2cdev>::new(i2c);
+	let mut sensor = ADXL343Interface::new(i2c);
 
 	sensor.with_settings(settings)?;
 	sensor.init()?; sensor.begin_measurements()?;
@@ -129,7 +130,7 @@ fn create_sensor_with_justification(mut sensor: SensorInterface, justification:
 	sensor.turn_off_measurements()?;
 	let (i2c, mut settings) = sensor.destroy();
 	settings.set_justification(justification);
-	let mut sensor = ADXL343Interface::<I2cdev>::new(i2c);
+	let mut sensor = ADXL343Interface::new(i2c);
 
 	sensor.with_settings(settings)?;
 	sensor.init()?; sensor.begin_measurements()?;
@@ -154,7 +155,7 @@ fn read_axis(sensor: &mut SensorInterface, axis: Axis)
 }
 
 pub fn setup_i2c_interface_with_adxl343() 
--> Result<ADXL343Interface<I2cdev>, I2CError>
+-> Result<SensorInterface, I2CError>
 {
     	//initializes an i2c device through the embedded linux hal lib
     	let i2c = I2cdev::new("/dev/i2c-1")?;